@@ -8,15 +8,23 @@ use gpui::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use ui::{prelude::Window, theme::ActiveTheme, IconName, StyledExt as _};
+use ui::{prelude::Window, theme::ActiveTheme, Icon, IconName, StyledExt as _};
 
 const RESIZE_HANDLE_SIZE: Pixels = Pixels(6.);
+const COLLAPSED_RAIL_SIZE: Pixels = Pixels(28.);
 
-use crate::{DraggedDock, Event};
+use crate::{util::ResultExt, DraggedDock, Event};
 
 use super::workspace::Workspace;
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+/// A panel button dragged along the collapsed rail of its dock, to reorder the panels.
+#[derive(Clone, Render)]
+pub struct DraggedPanel {
+    pub position: DockPosition,
+    pub ix: usize,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum DockPosition {
     Left,
@@ -25,12 +33,72 @@ pub enum DockPosition {
 }
 
 impl DockPosition {
+    pub fn all() -> [Self; 3] {
+        [Self::Left, Self::Bottom, Self::Right]
+    }
+
     pub fn axis(&self) -> Axis {
         match self {
             Self::Left | Self::Right => Axis::Horizontal,
             Self::Bottom => Axis::Vertical,
         }
     }
+
+    /// The dock on the other side of the center, `None` for the bottom dock.
+    pub fn opposite(&self) -> Option<DockPosition> {
+        match self {
+            Self::Left => Some(Self::Right),
+            Self::Right => Some(Self::Left),
+            Self::Bottom => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockState {
+    Open,
+    /// Closed, but shown as a thin rail of panel buttons to reopen it with.
+    Collapsed,
+    Closed,
+}
+
+/// Snap points applied while a dock is resized by dragging its handle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DockResizeSnap {
+    /// Fractions of the workspace size along the dock axis to snap to.
+    pub points: Vec<f32>,
+    /// How close the dragged size must be to a snap point to snap to it.
+    pub radius: Pixels,
+    /// Sizes below this threshold snap the dock closed.
+    pub hide_threshold: Pixels,
+}
+
+impl Default for DockResizeSnap {
+    fn default() -> Self {
+        Self {
+            points: vec![0.25, 0.5, 0.75],
+            radius: px(8.),
+            hide_threshold: px(40.),
+        }
+    }
+}
+
+impl DockResizeSnap {
+    /// Snap a dragged dock size, given the total available size along the dock axis.
+    ///
+    /// Returns `None` if the dock should be closed.
+    pub fn snap(&self, size: Pixels, total: Pixels) -> Option<Pixels> {
+        if size < self.hide_threshold {
+            return None;
+        }
+
+        let snapped = self
+            .points
+            .iter()
+            .map(|point| total * *point)
+            .find(|point| (size - *point).abs() <= self.radius);
+        Some(snapped.unwrap_or(size))
+    }
 }
 
 pub enum PanelEvent {
@@ -75,6 +143,32 @@ pub trait Panel: Focusable + EventEmitter<PanelEvent> + Render + Sized {
     }
 }
 
+/// A panel of any type to add at a given dock, see [`Workspace::add_panels`].
+pub struct DockPanel {
+    add: Box<dyn FnOnce(&mut Workspace, &mut Window, &mut App)>,
+}
+
+impl DockPanel {
+    /// Moves `panel` to `position` when added, unless it can't be positioned there, in
+    /// which case it goes to the dock at its own [`Panel::position`].
+    pub fn new<T: Panel>(position: DockPosition, panel: Entity<T>) -> Self {
+        Self {
+            add: Box::new(move |workspace, window, cx| {
+                panel.update(cx, |panel, cx| {
+                    if panel.can_position(position) && panel.position(window, cx) != position {
+                        panel.set_position(position, window, cx);
+                    }
+                });
+                workspace.add_panel(panel, window, cx);
+            }),
+        }
+    }
+
+    pub(crate) fn add_to(self, workspace: &mut Workspace, window: &mut Window, cx: &mut App) {
+        (self.add)(workspace, window, cx)
+    }
+}
+
 pub trait PanelHandle: Send + Sync {
     fn id(&self) -> EntityId;
     fn persistent_name(&self) -> &'static str;
@@ -160,8 +254,10 @@ struct PanelEntry {
 
 pub struct Dock {
     position: DockPosition,
+    workspace: WeakEntity<Workspace>,
     panel_entries: Vec<PanelEntry>,
     is_open: bool,
+    collapse_when_closed: bool,
     active_panel_index: usize,
     focus_handle: FocusHandle,
     resizeable: bool,
@@ -181,7 +277,7 @@ impl Dock {
         cx: &mut Context<Workspace>,
     ) -> Entity<Self> {
         let focus_handle = cx.focus_handle();
-        // let workspace = cx.view().clone();
+        let workspace = cx.entity().downgrade();
 
         let dock = cx.new(|cx: &mut Context<Self>| {
             let focus_subscription = cx.on_focus(&focus_handle, window, |dock, window, cx| {
@@ -192,9 +288,11 @@ impl Dock {
 
             Self {
                 position,
+                workspace,
                 panel_entries: Vec::new(),
                 active_panel_index: 0,
                 is_open: false,
+                collapse_when_closed: false,
                 focus_handle: focus_handle.clone(),
                 resizeable: true,
                 _subscriptions: [focus_subscription],
@@ -221,10 +319,74 @@ impl Dock {
         self.position
     }
 
+    /// Move the dock to `position`, updating the position of its panels.
+    pub(crate) fn set_position(
+        &mut self,
+        position: DockPosition,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.position = position;
+        for entry in &self.panel_entries {
+            entry.panel.set_position(position, window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Whether every panel of the dock can be positioned at `position`, see
+    /// [`Panel::can_position`].
+    pub(crate) fn panels_can_position(
+        &self,
+        position: DockPosition,
+        window: &Window,
+        cx: &App,
+    ) -> bool {
+        self.panel_entries
+            .iter()
+            .all(|entry| entry.panel.can_position(position, window, cx))
+    }
+
+    /// The side of the window the dock is shown on, which differs from its position when
+    /// the workspace is laid out right to left.
+    fn visual_position(&self, cx: &App) -> DockPosition {
+        self.workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).layout_direction())
+            .unwrap_or_default()
+            .visual_dock_position(self.position)
+    }
+
     pub fn is_open(&self) -> bool {
         self.is_open
     }
 
+    pub fn state(&self) -> DockState {
+        if self.is_open {
+            DockState::Open
+        } else if self.collapse_when_closed && !self.panel_entries.is_empty() {
+            DockState::Collapsed
+        } else {
+            DockState::Closed
+        }
+    }
+
+    /// The space the dock takes along its axis, e.g. the width of a side dock.
+    pub fn visible_size(&self, window: &Window, cx: &App) -> Pixels {
+        match self.state() {
+            DockState::Open => self
+                .visible_panel()
+                .map_or(px(0.), |panel| panel.size(window, cx)),
+            DockState::Collapsed => COLLAPSED_RAIL_SIZE,
+            DockState::Closed => px(0.),
+        }
+    }
+
+    /// Show the closed dock as a rail of panel buttons instead of hiding it entirely.
+    pub fn set_collapse_when_closed(&mut self, collapse: bool, cx: &mut Context<Self>) {
+        self.collapse_when_closed = collapse;
+        cx.notify();
+    }
+
     pub fn set_panel_zoomed(
         &mut self,
         panel: &AnyView,
@@ -358,6 +520,31 @@ impl Dock {
         self.panel_entries.len()
     }
 
+    /// Move the panel at `from_ix` to `to_ix`, the active panel stays active.
+    ///
+    /// Returns `false` if either index is out of bounds.
+    pub fn reorder_panel(&mut self, from_ix: usize, to_ix: usize, cx: &mut Context<Self>) -> bool {
+        let len = self.panel_entries.len();
+        if from_ix >= len || to_ix >= len {
+            return false;
+        }
+        if from_ix == to_ix {
+            return true;
+        }
+
+        let entry = self.panel_entries.remove(from_ix);
+        self.panel_entries.insert(to_ix, entry);
+        if self.active_panel_index == from_ix {
+            self.active_panel_index = to_ix;
+        } else if from_ix < self.active_panel_index && self.active_panel_index <= to_ix {
+            self.active_panel_index -= 1;
+        } else if to_ix <= self.active_panel_index && self.active_panel_index < from_ix {
+            self.active_panel_index += 1;
+        }
+        cx.notify();
+        true
+    }
+
     pub fn activate_panel(&mut self, panel_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
         if panel_ix != self.active_panel_index {
             if let Some(active_panel) = self.panel_entries.get(self.active_panel_index) {
@@ -405,6 +592,12 @@ impl Dock {
         }
     }
 
+    pub fn panel_index_for_persistent_name(&self, name: &str) -> Option<usize> {
+        self.panel_entries
+            .iter()
+            .position(|entry| entry.panel.persistent_name() == name)
+    }
+
     pub fn panel<T: Panel>(&self) -> Option<Entity<T>> {
         self.panel_entries
             .iter()
@@ -425,15 +618,118 @@ impl Dock {
     }
 }
 
+impl Dock {
+    fn open_from_rail(&mut self, panel_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.activate_panel(panel_ix, window, cx);
+
+        // Open through the workspace so zoom, focus and events are handled like a toggle.
+        let position = self.position;
+        self.workspace
+            .update(cx, |_, cx| {
+                cx.defer_in(window, move |workspace, window, cx| {
+                    if !workspace.dock_at(position).read(cx).is_open() {
+                        workspace.toggle_dock(position, window, cx);
+                    }
+                });
+            })
+            .log_err();
+    }
+
+    fn reorder_from_rail(
+        &mut self,
+        from_ix: usize,
+        to_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Reorder through the workspace so it emits `DockPanelsReordered`.
+        let position = self.position;
+        self.workspace
+            .update(cx, |_, cx| {
+                cx.defer_in(window, move |workspace, _, cx| {
+                    workspace.reorder_panel(position, from_ix, to_ix, cx);
+                });
+            })
+            .log_err();
+    }
+
+    fn render_rail(&self, window: &mut Window, cx: &mut Context<Self>) -> gpui::Div {
+        let position = self.position;
+        div()
+            .key_context("Dock")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_none()
+            .items_center()
+            .gap_1()
+            .p_1()
+            .bg(cx.theme().panel)
+            .border_color(cx.theme().border)
+            .map(|this| match self.position.axis() {
+                Axis::Horizontal => this.w(COLLAPSED_RAIL_SIZE).h_full().flex_col(),
+                Axis::Vertical => this.h(COLLAPSED_RAIL_SIZE).w_full().flex_row(),
+            })
+            .map(|this| match self.visual_position(cx) {
+                DockPosition::Left => this.border_r_1(),
+                DockPosition::Right => this.border_l_1(),
+                DockPosition::Bottom => this.border_t_1(),
+            })
+            .children(self.panel_entries.iter().enumerate().map(|(ix, entry)| {
+                let button = div()
+                    .id(ix)
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .size(px(20.))
+                    .rounded_sm()
+                    .hover(|this| this.bg(cx.theme().accent))
+                    .on_click(
+                        cx.listener(move |dock, _, window, cx| dock.open_from_rail(ix, window, cx)),
+                    )
+                    .on_drag(DraggedPanel { position, ix }, |panel, _, _, cx| {
+                        cx.stop_propagation();
+                        cx.new(|_| panel.clone())
+                    })
+                    .can_drop(move |dragged, _, _| {
+                        dragged
+                            .downcast_ref::<DraggedPanel>()
+                            .is_some_and(|panel| panel.position == position)
+                    })
+                    .drag_over::<DraggedPanel>(|this, _, _, cx| this.bg(cx.theme().drop_target))
+                    .on_drop(cx.listener(move |dock, panel: &DraggedPanel, window, cx| {
+                        if panel.position == position {
+                            dock.reorder_from_rail(panel.ix, ix, window, cx);
+                        }
+                    }));
+                match entry.panel.icon(window, cx) {
+                    Some(icon) => button.child(Icon::new(icon).size(px(14.))),
+                    None => button.child(
+                        entry
+                            .panel
+                            .persistent_name()
+                            .chars()
+                            .take(1)
+                            .collect::<String>()
+                            .to_uppercase(),
+                    ),
+                }
+            }))
+    }
+}
+
 impl Render for Dock {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl gpui::IntoElement {
         if self.visible_entry().is_none() {
+            if self.state() == DockState::Collapsed {
+                return self.render_rail(window, cx);
+            }
             return div().key_context("Dock").track_focus(&self.focus_handle);
         }
 
         let entry = self.visible_entry().unwrap();
         let size = entry.panel.size(window, cx);
         let position = self.position;
+        let visual_position = self.visual_position(cx);
         let create_resize_handle = || {
             let handle = div()
                 .id("resize-handle")
@@ -458,7 +754,7 @@ impl Render for Dock {
                 )
                 .occlude();
 
-            match self.position() {
+            match visual_position {
                 DockPosition::Left => deferred(
                     handle
                         .absolute()
@@ -500,7 +796,7 @@ impl Render for Dock {
                 Axis::Horizontal => this.w(size).h_full().flex_row(),
                 Axis::Vertical => this.h(size).w_full().flex_col(),
             })
-            .map(|this| match self.position() {
+            .map(|this| match visual_position {
                 DockPosition::Left => this.border_r_1(),
                 DockPosition::Right => this.border_l_1(),
                 DockPosition::Bottom => this.border_t_1(),
@@ -521,3 +817,92 @@ impl Render for Dock {
             .when(self.resizeable, |this| this.child(create_resize_handle()))
     }
 }
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test {
+    use gpui::{
+        div, px, App, Context, EventEmitter, FocusHandle, Focusable, IntoElement, Pixels, Render,
+    };
+    use ui::prelude::Window;
+
+    use super::{DockPosition, Panel, PanelEvent};
+
+    /// A placeholder panel for filling docks in tests.
+    pub struct TestPanel {
+        pub position: DockPosition,
+        /// The only position the panel can be moved to, if any, see [`Panel::can_position`].
+        pub fixed_position: Option<DockPosition>,
+        focus_handle: FocusHandle,
+    }
+
+    impl TestPanel {
+        pub fn new(position: DockPosition, cx: &mut Context<Self>) -> Self {
+            Self {
+                position,
+                fixed_position: None,
+                focus_handle: cx.focus_handle(),
+            }
+        }
+
+        /// Only allow the panel at its current position.
+        pub fn fixed(mut self) -> Self {
+            self.fixed_position = Some(self.position);
+            self
+        }
+    }
+
+    impl EventEmitter<PanelEvent> for TestPanel {}
+
+    impl Focusable for TestPanel {
+        fn focus_handle(&self, _: &App) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestPanel {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    impl Panel for TestPanel {
+        fn persistent_name() -> &'static str {
+            "TestPanel"
+        }
+
+        fn position(&self, _: &Window, _: &App) -> DockPosition {
+            self.position
+        }
+
+        fn can_position(&self, position: DockPosition) -> bool {
+            self.fixed_position.is_none() || self.fixed_position == Some(position)
+        }
+
+        fn set_position(&mut self, position: DockPosition, _: &mut Window, _: &mut Context<Self>) {
+            self.position = position;
+        }
+
+        fn size(&self, _: &Window, _: &App) -> Pixels {
+            px(200.)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opposite_is_an_involution() {
+        for position in DockPosition::all() {
+            match position.opposite() {
+                Some(opposite) => {
+                    assert_ne!(opposite, position);
+                    assert_eq!(opposite.opposite(), Some(position));
+                    assert_eq!(opposite.axis(), position.axis());
+                }
+                None => assert_eq!(position, DockPosition::Bottom),
+            }
+        }
+    }
+}
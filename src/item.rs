@@ -337,3 +337,81 @@ impl<T: Item> WeakItemHandle for WeakEntity<T> {
         self.upgrade().map(|v| Box::new(v) as Box<dyn ItemHandle>)
     }
 }
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test {
+    use gpui::{
+        div, AnyElement, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle,
+        Focusable, IntoElement, ParentElement as _, Render, SharedString,
+    };
+    use ui::prelude::Window;
+
+    use super::{Item, ItemEvent, TabContentParams};
+    use crate::WorkspaceId;
+
+    /// A placeholder item for filling panes in tests.
+    pub struct TestItem {
+        pub label: SharedString,
+        /// Whether [`Item::clone_on_split`] clones the item.
+        pub cloneable: bool,
+        focus_handle: FocusHandle,
+    }
+
+    impl TestItem {
+        pub fn new(cx: &mut Context<Self>) -> Self {
+            Self {
+                label: "Test".into(),
+                cloneable: false,
+                focus_handle: cx.focus_handle(),
+            }
+        }
+
+        pub fn with_label(mut self, label: impl Into<SharedString>) -> Self {
+            self.label = label.into();
+            self
+        }
+
+        pub fn with_cloneable(mut self, cloneable: bool) -> Self {
+            self.cloneable = cloneable;
+            self
+        }
+    }
+
+    impl EventEmitter<ItemEvent> for TestItem {}
+
+    impl Focusable for TestItem {
+        fn focus_handle(&self, _: &App) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestItem {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div().child(self.label.clone())
+        }
+    }
+
+    impl Item for TestItem {
+        type Event = ItemEvent;
+
+        fn tab_content(&self, _: TabContentParams, _: &Window, _: &App) -> AnyElement {
+            div().child(self.label.clone()).into_any_element()
+        }
+
+        fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+            f(*event)
+        }
+
+        fn clone_on_split(
+            &self,
+            _: Option<WorkspaceId>,
+            _: &mut Window,
+            cx: &mut Context<Self>,
+        ) -> Option<Entity<Self>> {
+            self.cloneable.then(|| {
+                let label = self.label.clone();
+                cx.new(|cx| Self::new(cx).with_label(label).with_cloneable(true))
+            })
+        }
+    }
+}
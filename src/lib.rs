@@ -5,4 +5,5 @@ pub mod dock;
 pub mod item;
 pub mod pane;
 pub mod pane_group;
+pub mod persistence;
 pub use workspace::*;
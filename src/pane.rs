@@ -1,7 +1,7 @@
 use std::{
     any::Any,
     cmp,
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     fmt, mem,
     ops::ControlFlow,
     rc::Rc,
@@ -10,11 +10,12 @@ use std::{
 
 use anyhow::Result;
 use gpui::{
-    actions, div, impl_internal_actions, prelude::FluentBuilder as _, px, App, AppContext, Context,
-    DefiniteLength, DragMoveEvent, Element as _, Entity, EntityId, EventEmitter, FocusHandle,
-    FocusOutEvent, Focusable, InteractiveElement as _, IntoElement, KeyContext, ParentElement,
-    Pixels, Point, Render, ScrollHandle, StatefulInteractiveElement, Styled, Subscription, Task,
-    WeakEntity, WeakFocusHandle,
+    actions, div, impl_internal_actions, prelude::FluentBuilder as _, px, Along, AnyElement, App,
+    AppContext, Axis, Bounds, Context, DefiniteLength, DragMoveEvent, Element as _, Entity,
+    EntityId, EventEmitter, FocusHandle, FocusOutEvent, Focusable, InteractiveElement as _,
+    IntoElement, KeyContext, MouseMoveEvent, ParentElement, Pixels, Point, Render, ScrollHandle,
+    SharedString, Size, StatefulInteractiveElement, Styled, Subscription, Task, WeakEntity,
+    WeakFocusHandle,
 };
 use serde::Deserialize;
 
@@ -30,7 +31,8 @@ use ui::{
 
 use super::{
     item::{ItemHandle, TabContentParams},
-    pane_group::SplitDirection,
+    pane_group::{self, SplitDirection},
+    persistence::SerializedPaneId,
     workspace::Workspace,
 };
 
@@ -67,6 +69,7 @@ pub enum Event {
     RemoveItem { item_id: EntityId },
     Split(SplitDirection),
     ChangeItemTitle,
+    ChangeLabel,
     Focus,
     ZoomIn,
     ZoomOut,
@@ -93,6 +96,7 @@ impl fmt::Debug for Event {
                 .field("direction", direction)
                 .finish(),
             Event::ChangeItemTitle => f.write_str("ChangeItemTitle"),
+            Event::ChangeLabel => f.write_str("ChangeLabel"),
             Event::Focus => f.write_str("Focus"),
             Event::ZoomIn => f.write_str("ZoomIn"),
             Event::ZoomOut => f.write_str("ZoomOut"),
@@ -123,17 +127,39 @@ impl Render for DraggedTab {
     }
 }
 
+/// A whole pane being dragged by its tab bar gutter to a new place in its split.
+#[derive(Clone)]
+pub struct DraggedPane {
+    pub pane: Entity<Pane>,
+    pub label: SharedString,
+}
+
+impl Render for DraggedPane {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        Tab::new("", Label::new(self.label.clone()))
+            .selected(true)
+            .into_element()
+    }
+}
+
 #[allow(clippy::type_complexity)]
 /// A pane
 pub struct Pane {
     pub(crate) workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
     items: Vec<Box<dyn ItemHandle>>,
+    pinned_items: HashSet<EntityId>,
     zoomed: bool,
     was_focused: bool,
     last_focus_handle_by_item: HashMap<EntityId, WeakFocusHandle>,
     active_item_index: usize,
     drag_split_direction: Option<SplitDirection>,
+    label: Option<SharedString>,
+    serialized_id: SerializedPaneId,
+    empty_view: Option<Rc<dyn Fn(&mut Window, &mut App) -> AnyElement>>,
+    deferred_items: Option<Box<dyn FnOnce(&mut Pane, &mut Window, &mut Context<Pane>)>>,
+    navigation_anchor: Option<Rc<dyn Fn(Bounds<Pixels>, &App) -> Bounds<Pixels>>>,
+    min_size: Option<Size<Pixels>>,
     can_drop_predicate: Option<Arc<dyn Fn(&dyn Any, &mut Window, &mut App) -> bool>>,
     custom_drop_handle: Option<
         Arc<dyn Fn(&mut Pane, &dyn Any, &mut Window, &mut Context<Pane>) -> ControlFlow<(), ()>>,
@@ -167,10 +193,17 @@ impl Pane {
             workspace,
             focus_handle,
             items: Vec::new(),
+            pinned_items: HashSet::new(),
             zoomed: false,
             was_focused: false,
             active_item_index: 0,
             drag_split_direction: None,
+            label: None,
+            serialized_id: SerializedPaneId::default(),
+            empty_view: None,
+            deferred_items: None,
+            navigation_anchor: None,
+            min_size: None,
             last_focus_handle_by_item: HashMap::new(),
             can_drop_predicate,
             custom_drop_handle: None,
@@ -193,6 +226,110 @@ impl Pane {
         self.should_display_tab_bar = Rc::new(f);
     }
 
+    /// Set what is shown while the pane has no items, `None` for the default hint.
+    pub fn set_empty_view(
+        &mut self,
+        empty_view: Option<Rc<dyn Fn(&mut Window, &mut App) -> AnyElement>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.empty_view = empty_view;
+        cx.notify();
+    }
+
+    /// The size the pane can't be resized below along `axis`.
+    pub fn min_size(&self, axis: Axis) -> Pixels {
+        self.min_size
+            .map(|min_size| min_size.along(axis))
+            .unwrap_or_else(|| pane_group::default_min_size(axis))
+    }
+
+    /// Set the size the pane can't be resized below, e.g. for a terminal that needs more
+    /// width than an editor. `None` uses the same minimum as other panes.
+    pub fn set_min_size(&mut self, min_size: Option<Size<Pixels>>, cx: &mut Context<Self>) {
+        self.min_size = min_size;
+        cx.notify();
+    }
+
+    /// Set how the area directional navigation starts from is derived from the pane bounds,
+    /// e.g. to exclude a vertical tab bar. `None` uses the whole pane.
+    pub fn set_navigation_anchor(
+        &mut self,
+        navigation_anchor: Option<Rc<dyn Fn(Bounds<Pixels>, &App) -> Bounds<Pixels>>>,
+    ) {
+        self.navigation_anchor = navigation_anchor;
+    }
+
+    /// The area within `bounds`, the pane's bounds, that directional navigation starts from.
+    pub fn navigation_anchor(&self, bounds: Bounds<Pixels>, cx: &App) -> Bounds<Pixels> {
+        match &self.navigation_anchor {
+            Some(navigation_anchor) => navigation_anchor(bounds, cx),
+            None => bounds,
+        }
+    }
+
+    /// The id identifying the pane in serialized layouts, see [`SerializedPaneId`].
+    pub fn serialized_id(&self) -> SerializedPaneId {
+        self.serialized_id
+    }
+
+    pub(crate) fn set_serialized_id(&mut self, serialized_id: SerializedPaneId) {
+        self.serialized_id = serialized_id;
+    }
+
+    pub fn label(&self) -> Option<&SharedString> {
+        self.label.as_ref()
+    }
+
+    /// Set the user-visible label of this pane, `None` to fall back to the active item.
+    pub fn set_label(&mut self, label: Option<SharedString>, cx: &mut Context<Self>) {
+        if self.label != label {
+            self.label = label;
+            cx.emit(Event::ChangeLabel);
+            cx.notify();
+        }
+    }
+
+    /// The label set with [`Pane::set_label`], or else the description of the active item.
+    pub fn display_label(&self, cx: &App) -> Option<SharedString> {
+        self.label.clone().or_else(|| {
+            let item = self.active_item()?;
+            item.tab_description(0, cx).or_else(|| item.tab_tooltip(cx))
+        })
+    }
+
+    /// Defer building the items of this pane until it is first focused.
+    ///
+    /// `build_item` is called for each descriptor in order, descriptors it returns `None`
+    /// for are skipped. Useful when restoring many panes of which only one is visible.
+    pub fn defer_items<D: 'static>(
+        &mut self,
+        descriptors: Vec<D>,
+        active_item_index: usize,
+        build_item: impl Fn(D, &mut Window, &mut Context<Pane>) -> Option<Box<dyn ItemHandle>> + 'static,
+    ) {
+        self.deferred_items = Some(Box::new(move |pane, window, cx| {
+            for descriptor in descriptors {
+                if let Some(item) = build_item(descriptor, window, cx) {
+                    pane.add_item(item, false, false, None, window, cx);
+                }
+            }
+            let focus_item = pane.has_focus(window, cx);
+            pane.activate_item(active_item_index, false, focus_item, window, cx);
+        }));
+    }
+
+    pub fn has_deferred_items(&self) -> bool {
+        self.deferred_items.is_some()
+    }
+
+    /// Build the items passed to [`Pane::defer_items`], if they haven't been built yet.
+    pub fn load_deferred_items(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(load) = self.deferred_items.take() {
+            load(self, window, cx);
+            cx.notify();
+        }
+    }
+
     pub fn set_custom_drop_handle<F>(
         &mut self,
         _window: &mut Window,
@@ -250,6 +387,36 @@ impl Pane {
         })
     }
 
+    /// Close the items matching `should_close`, skipping any pinned items.
+    pub fn close_unpinned_items(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Pane>,
+        should_close: impl Fn(EntityId) -> bool,
+    ) -> Task<Result<()>> {
+        let pinned_items = self.pinned_items.clone();
+        self.close_items(window, cx, move |item_id| {
+            !pinned_items.contains(&item_id) && should_close(item_id)
+        })
+    }
+
+    /// Pin an item, pinned items are kept open by the workspace bulk-close operations.
+    pub fn pin_item(&mut self, item_id: EntityId, cx: &mut Context<Self>) {
+        if self.index_for_item_id(item_id).is_some() && self.pinned_items.insert(item_id) {
+            cx.notify();
+        }
+    }
+
+    pub fn unpin_item(&mut self, item_id: EntityId, cx: &mut Context<Self>) {
+        if self.pinned_items.remove(&item_id) {
+            cx.notify();
+        }
+    }
+
+    pub fn is_item_pinned(&self, item_id: EntityId) -> bool {
+        self.pinned_items.contains(&item_id)
+    }
+
     pub fn remove_item(
         &mut self,
         item_index: usize,
@@ -279,6 +446,7 @@ impl Pane {
         }
 
         let item = self.items.remove(item_index);
+        self.pinned_items.remove(&item.item_id());
         cx.emit(Event::RemoveItem {
             item_id: item.item_id(),
         });
@@ -375,6 +543,42 @@ impl Pane {
         cx.emit(Event::AddItem { item });
     }
 
+    /// Replace the items of the pane, used when restoring a layout.
+    ///
+    /// Unlike removing items one by one, this never asks the workspace to remove the pane.
+    pub(crate) fn set_items(
+        &mut self,
+        items: Vec<Box<dyn ItemHandle>>,
+        active_item_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let previous_items = mem::replace(&mut self.items, items);
+        for item in &previous_items {
+            if self.index_for_item_id(item.item_id()).is_none() {
+                self.pinned_items.remove(&item.item_id());
+                item.deactivated(window, cx);
+                cx.emit(Event::RemoveItem {
+                    item_id: item.item_id(),
+                });
+            }
+        }
+        for item in &self.items {
+            if !previous_items
+                .iter()
+                .any(|previous| previous.item_id() == item.item_id())
+            {
+                cx.emit(Event::AddItem {
+                    item: item.boxed_clone(),
+                });
+            }
+        }
+
+        self.active_item_index = active_item_index.min(self.items.len().saturating_sub(1));
+        cx.emit(Event::ActivateItem { local: false });
+        cx.notify();
+    }
+
     pub fn items_len(&self) -> usize {
         self.items.len()
     }
@@ -387,6 +591,10 @@ impl Pane {
         self.items.get(self.active_item_index).cloned()
     }
 
+    pub fn index_for_item_id(&self, item_id: EntityId) -> Option<usize> {
+        self.items.iter().position(|item| item.item_id() == item_id)
+    }
+
     pub fn pixel_position_of_cursor(&self, cx: &App) -> Option<Point<Pixels>> {
         self.items
             .get(self.active_item_index)?
@@ -587,7 +795,7 @@ impl Pane {
         &mut self,
         event: &DragMoveEvent<T>,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) {
         if !self.can_split {
             return;
@@ -625,9 +833,18 @@ impl Pane {
 
         if direction != self.drag_split_direction {
             self.drag_split_direction = direction;
+            cx.notify();
         }
     }
 
+    /// The zone of the pane a dragged item is hovering over.
+    ///
+    /// `None` means the center, where dropping moves the item into this pane, otherwise
+    /// dropping splits the pane in the returned direction.
+    pub fn drag_split_direction(&self) -> Option<SplitDirection> {
+        self.drag_split_direction
+    }
+
     fn handle_tab_drop(
         &mut self,
         dragged_tab: &DraggedTab,
@@ -641,7 +858,7 @@ impl Pane {
             }
         }
         let mut to_pane = cx.entity().clone();
-        let split_direction = self.drag_split_direction;
+        let split_direction = self.drag_split_direction.take();
         let item_id = dragged_tab.item.item_id();
 
         let from_pane = dragged_tab.pane.clone();
@@ -649,7 +866,10 @@ impl Pane {
             .update(cx, |_, cx| {
                 cx.defer_in(window, move |workspace, window, cx| {
                     if let Some(split_direction) = split_direction {
-                        to_pane = workspace.split_pane(to_pane, split_direction, window, cx);
+                        match workspace.split_pane(to_pane, split_direction, None, window, cx) {
+                            Some(new_pane) => to_pane = new_pane,
+                            None => return,
+                        }
                     }
                     workspace.move_item(from_pane, to_pane, item_id, ix, window, cx);
                 });
@@ -657,6 +877,23 @@ impl Pane {
             .log_err();
     }
 
+    fn handle_pane_drop(
+        &mut self,
+        dragged_pane: &DraggedPane,
+        window: &mut Window,
+        cx: &mut Context<'_, Self>,
+    ) {
+        let pane = dragged_pane.pane.clone();
+        let position = window.mouse_position();
+        self.workspace
+            .update(cx, |_, cx| {
+                cx.defer_in(window, move |workspace, window, cx| {
+                    workspace.move_pane_in_split(&pane, position, window, cx);
+                });
+            })
+            .log_err();
+    }
+
     fn focus_in(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if !self.was_focused {
             self.was_focused = true;
@@ -814,12 +1051,24 @@ impl Pane {
                     .flex_grow()
                     .drag_over::<DraggedTab>(|bar, _, _, cx| bar.bg(cx.theme().drop_target))
                     .drag_over::<DraggedSelection>(|bar, _, _, cx| bar.bg(cx.theme().drop_target))
+                    .drag_over::<DraggedPane>(|bar, _, _, cx| bar.bg(cx.theme().drop_target))
+                    .on_drag(
+                        DraggedPane {
+                            pane: cx.entity().clone(),
+                            label: self
+                                .label
+                                .clone()
+                                .unwrap_or_else(|| SharedString::from("Pane")),
+                        },
+                        |dragged_pane, _, _, cx| cx.new(|_| dragged_pane.clone()),
+                    )
                     .on_drop(
                         cx.listener(move |this, dragged_tab: &DraggedTab, window, cx| {
                             this.drag_split_direction = None;
                             this.handle_tab_drop(dragged_tab, this.items.len(), window, cx)
                         }),
-                    ),
+                    )
+                    .on_drop(cx.listener(Self::handle_pane_drop)),
             )
     }
 
@@ -832,6 +1081,17 @@ impl Pane {
         self.zoomed
     }
 
+    pub fn toggle_zoom(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.zoomed {
+            cx.emit(Event::ZoomOut);
+        } else if !self.items.is_empty() {
+            if !self.focus_handle.contains_focused(window, cx) {
+                cx.focus_self(window);
+            }
+            cx.emit(Event::ZoomIn);
+        }
+    }
+
     pub fn active_item_index(&self) -> usize {
         self.active_item_index
     }
@@ -896,6 +1156,19 @@ impl Render for Pane {
             .size_full()
             .flex_none()
             .overflow_hidden()
+            .on_mouse_move(cx.listener(|pane, event: &MouseMoveEvent, window, cx| {
+                // Don't follow the mouse while dragging, e.g. a tab or a divider
+                if event.pressed_button.is_some() {
+                    return;
+                }
+                let this = cx.entity();
+                let focus_handle = pane.focus_handle.clone();
+                pane.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.pane_hovered(&this, &focus_handle, window, cx)
+                    })
+                    .ok();
+            }))
             .on_action(cx.listener(|pane, _: &SplitLeft, window, cx| {
                 pane.split(SplitDirection::Left, window, cx)
             }))
@@ -996,7 +1269,10 @@ impl Render for Pane {
                                 .h_flex()
                                 .size_full()
                                 .justify_center()
-                                .child(Label::new("No panel."))
+                                .map(|this| match self.empty_view.clone() {
+                                    Some(empty_view) => this.child(empty_view(window, cx)),
+                                    None => this.child(Label::new("No panel.")),
+                                })
                         }
                     })
                     .child(
@@ -1007,6 +1283,7 @@ impl Render for Pane {
                             .bg(cx.theme().drop_target)
                             .group_drag_over::<DraggedTab>("", |style| style.visible())
                             .group_drag_over::<DraggedSelection>("", |style| style.visible())
+                            .group_drag_over::<DraggedPane>("", |style| style.visible())
                             .when_some(self.can_drop_predicate.clone(), |this, p| {
                                 this.can_drop(move |a, window, cx| p(a, window, cx))
                             })
@@ -1018,6 +1295,7 @@ impl Render for Pane {
                                     cx,
                                 )
                             }))
+                            .on_drop(cx.listener(Self::handle_pane_drop))
                             .map(|div| {
                                 let size = DefiniteLength::Fraction(0.5);
                                 match self.drag_split_direction {
@@ -1,20 +1,115 @@
-use anyhow::{anyhow, Result};
-use element::pane_axis;
+use element::{flex_values_in_bounds, pane_axis, resize_flexes};
 use gpui::{
-    div, point, size, AnyView, AnyWeakView, Axis, Bounds, Context, Element as _, Entity,
-    IntoElement, ParentElement as _, Pixels, Point, StyleRefinement, Styled as _,
+    div, point, prelude::FluentBuilder as _, px, size, Along, AnyView, AnyWeakView, App, Axis,
+    Bounds, Context, Element as _, Entity, EntityId, Hsla, IntoElement, ParentElement as _, Pixels,
+    Point, Size, StyleRefinement, Styled as _,
 };
 use parking_lot::Mutex;
 use serde::Deserialize;
-use std::sync::Arc;
-use ui::{prelude::Window, StyledExt as _};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, mem,
+    sync::Arc,
+};
+use ui::{prelude::Window, theme::ActiveTheme as _, StyledExt as _};
 
-use super::{pane::Pane, workspace::Workspace};
+use super::{
+    pane::Pane,
+    persistence::{SerializedPane, SerializedPaneGroup},
+    workspace::Workspace,
+};
 
+/// Default size of the hitbox used to grab the divider between two panes.
 pub const HANDLE_HITBOX_SIZE: f32 = 4.0;
+/// Default thickness of the visible divider between two panes.
+pub const DIVIDER_SIZE: f32 = 1.0;
 const HORIZONTAL_MIN_SIZE: f32 = 80.;
 const VERTICAL_MIN_SIZE: f32 = 100.;
 
+/// The size panes can't be resized below along `axis`, unless they set their own with
+/// [`Pane::set_min_size`].
+pub(crate) fn default_min_size(axis: Axis) -> Pixels {
+    match axis {
+        Axis::Horizontal => px(HORIZONTAL_MIN_SIZE),
+        Axis::Vertical => px(VERTICAL_MIN_SIZE),
+    }
+}
+/// Size a collapsed member of a [`PaneAxis`] is laid out with.
+const COLLAPSED_MEMBER_SIZE: f32 = 4.;
+/// The smallest share of a pane's space a split with a ratio gives either side.
+const MIN_SPLIT_RATIO: f32 = 0.05;
+
+/// Why an operation on a [`PaneGroup`] failed.
+///
+/// Converts into [`anyhow::Error`] with `?`, for callers that don't need to tell the cases
+/// apart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaneGroupError {
+    /// The pane isn't part of the group.
+    PaneNotFound,
+    /// The group is a single pane, so it has no splits to operate on.
+    NoSplits,
+    /// There is no split at this path of member indices.
+    SplitNotFound(Vec<usize>),
+    /// The split has no divider with this index.
+    DividerNotFound(usize),
+    /// The divider can't be moved, e.g. since a member next to it is collapsed.
+    DividerImmovable(usize),
+    /// The split hasn't been rendered yet, so its size isn't known.
+    NotLaidOut,
+    /// The tree violates an invariant, as described, found by [`PaneGroup::validate`].
+    InvalidLayout(String),
+}
+
+impl fmt::Display for PaneGroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaneGroupError::PaneNotFound => f.write_str("Pane not found"),
+            PaneGroupError::NoSplits => f.write_str("Pane group has no splits"),
+            PaneGroupError::SplitNotFound(axis_path) => {
+                write!(f, "No split at path {axis_path:?}")
+            }
+            PaneGroupError::DividerNotFound(ix) => write!(f, "Divider {ix} not found"),
+            PaneGroupError::DividerImmovable(ix) => write!(f, "Divider {ix} can't be moved"),
+            PaneGroupError::NotLaidOut => f.write_str("Split has not been laid out yet"),
+            PaneGroupError::InvalidLayout(description) => {
+                write!(f, "Invalid pane layout: {description}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaneGroupError {}
+
+/// Options controlling the layout and resizing of the splits in a [`PaneGroup`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaneGroupOptions {
+    /// Size of the hitbox used to grab the divider between two panes.
+    pub handle_hitbox_size: Pixels,
+    /// Thickness of the visible divider between two panes, independent of its hitbox.
+    pub divider_size: Pixels,
+    /// Color of the divider between two panes, the theme border color if `None`.
+    pub divider_color: Option<Hsla>,
+    /// The largest fraction of an axis a single member can be resized to, if any.
+    ///
+    /// A ratio below an even share of the axis is treated as an even share.
+    pub max_flex_ratio: Option<f32>,
+    /// Whether to outline the active pane when there is more than one pane.
+    pub highlight_active_pane: bool,
+}
+
+impl Default for PaneGroupOptions {
+    fn default() -> Self {
+        Self {
+            handle_hitbox_size: px(HANDLE_HITBOX_SIZE),
+            divider_size: px(DIVIDER_SIZE),
+            divider_color: None,
+            max_flex_ratio: None,
+            highlight_active_pane: true,
+        }
+    }
+}
+
 /// One or many panes, arranged in a horizontal or vertical axis due to a split.
 /// Panes have all their tabs and capabilities preserved, and can be split again or resized.
 /// Single-pane group is a regular pane.
@@ -35,17 +130,37 @@ impl PaneGroup {
         old_pane: &Entity<Pane>,
         new_pane: &Entity<Pane>,
         direction: SplitDirection,
-    ) -> Result<()> {
+    ) -> Result<(), PaneGroupError> {
+        self.split_with_ratio(old_pane, new_pane, direction, None)
+    }
+
+    /// Split `old_pane` like [`PaneGroup::split`], giving `new_pane` the `ratio` share of
+    /// the space `old_pane` had, e.g. `0.3` for a 70/30 split.
+    ///
+    /// `None` splits the space evenly. Either way, when `new_pane` is added to an existing
+    /// axis, the other members of the axis keep their sizes.
+    pub fn split_with_ratio(
+        &mut self,
+        old_pane: &Entity<Pane>,
+        new_pane: &Entity<Pane>,
+        direction: SplitDirection,
+        ratio: Option<f32>,
+    ) -> Result<(), PaneGroupError> {
+        let ratio = ratio
+            .filter(|ratio| ratio.is_finite())
+            .map(|ratio| ratio.max(MIN_SPLIT_RATIO).min(1. - MIN_SPLIT_RATIO))
+            .unwrap_or(0.5);
         match &mut self.root {
             Member::Pane(pane) => {
                 if pane == old_pane {
-                    self.root = Member::new_axis(old_pane.clone(), new_pane.clone(), direction);
+                    self.root =
+                        Member::new_axis(old_pane.clone(), new_pane.clone(), direction, ratio);
                     Ok(())
                 } else {
-                    Err(anyhow!("Pane not found"))
+                    Err(PaneGroupError::PaneNotFound)
                 }
             }
-            Member::Axis(axis) => axis.split(old_pane, new_pane, direction),
+            Member::Axis(axis) => axis.split(old_pane, new_pane, direction, ratio),
         }
     }
 
@@ -67,7 +182,7 @@ impl PaneGroup {
     /// - Ok(true) if it found and removed a pane
     /// - Ok(false) if it found but did not remove the pane
     /// - Err(_) if it did not find the pane
-    pub fn remove(&mut self, pane: &Entity<Pane>) -> Result<bool> {
+    pub fn remove(&mut self, pane: &Entity<Pane>) -> Result<bool, PaneGroupError> {
         match &mut self.root {
             Member::Pane(_) => Ok(false),
             Member::Axis(axis) => {
@@ -79,6 +194,66 @@ impl PaneGroup {
         }
     }
 
+    /// Take `pane` out of wherever it is nested and make it a direct member of the root
+    /// split, at the edge of the layout `direction` points to. If the root isn't split
+    /// along `direction`, it's wrapped in a new split with `pane` next to it.
+    ///
+    /// A pane that already is a direct member of a root split along `direction` is just
+    /// moved to that edge.
+    pub fn promote_pane_to_root(
+        &mut self,
+        pane: &Entity<Pane>,
+        direction: SplitDirection,
+    ) -> Result<(), PaneGroupError> {
+        if !self.root.contains(pane) {
+            return Err(PaneGroupError::PaneNotFound);
+        }
+        let Member::Axis(root) = &mut self.root else {
+            return Err(PaneGroupError::NoSplits);
+        };
+        if root.axis == direction.axis() {
+            if let Some(ix) = root.member_index(pane) {
+                let edge = if direction.increasing() {
+                    root.members.len() - 1
+                } else {
+                    0
+                };
+                root.reorder_member(ix, edge);
+                return Ok(());
+            }
+        }
+
+        self.remove(pane)?;
+        let member = Member::Pane(pane.clone());
+        match &mut self.root {
+            Member::Axis(root) if root.axis == direction.axis() => {
+                let ix = if direction.increasing() {
+                    root.members.len()
+                } else {
+                    0
+                };
+                root.insert_member(ix, member);
+            }
+            _ => {
+                let old_root = mem::replace(&mut self.root, Member::Pane(pane.clone()));
+                let members = if direction.increasing() {
+                    vec![old_root, member]
+                } else {
+                    vec![member, old_root]
+                };
+                self.root = Member::Axis(PaneAxis::new(direction.axis(), members));
+            }
+        }
+        Ok(())
+    }
+
+    /// Put `new` in the place of `old`, keeping the layout around it.
+    ///
+    /// Unlike [`PaneGroup::swap`], `new` must not be part of the group yet.
+    pub fn replace(&mut self, old: &Entity<Pane>, new: Entity<Pane>) -> Result<(), PaneGroupError> {
+        self.root.replace(old, new)
+    }
+
     pub fn swap(&mut self, from: &Entity<Pane>, to: &Entity<Pane>) {
         match &mut self.root {
             Member::Pane(_) => {}
@@ -86,15 +261,174 @@ impl PaneGroup {
         };
     }
 
+    /// Flip the axis directly containing `pane` between horizontal and vertical,
+    /// keeping the order of its members and resetting their sizes.
+    ///
+    /// Returns `false` if `pane` isn't part of a split.
+    pub fn rotate_axis(&mut self, pane: &Entity<Pane>) -> bool {
+        let Some(axis) = self.axis_containing_mut(pane) else {
+            return false;
+        };
+        axis.rotate();
+        true
+    }
+
+    /// Shrink `pane` to a thin strip in its split, keeping it and its items alive.
+    ///
+    /// Returns `false` if `pane` isn't part of a split, is already collapsed, or is the
+    /// last expanded member of its split.
+    pub fn collapse_pane(&mut self, pane: &Entity<Pane>) -> bool {
+        let Some(axis) = self.axis_containing_mut(pane) else {
+            return false;
+        };
+        axis.member_index(pane)
+            .is_some_and(|ix| axis.collapse_member(ix))
+    }
+
+    /// Restore a pane collapsed by [`PaneGroup::collapse_pane`] to its previous size.
+    ///
+    /// Returns `false` if `pane` isn't collapsed.
+    pub fn expand_pane(&mut self, pane: &Entity<Pane>) -> bool {
+        let Some(axis) = self.axis_containing_mut(pane) else {
+            return false;
+        };
+        axis.member_index(pane)
+            .is_some_and(|ix| axis.expand_member(ix))
+    }
+
+    /// Move a divider by `delta` the same way dragging it does.
+    ///
+    /// `axis_path` addresses the split the divider belongs to by the member indices leading
+    /// to it from the root split, an empty path being the root itself. `divider_index` is
+    /// the index of the member before the divider. The split must have been laid out, since
+    /// its size is needed to convert `delta` into flexes.
+    pub fn resize_split(
+        &self,
+        axis_path: &[usize],
+        divider_index: usize,
+        delta: Pixels,
+        max_flex_ratio: Option<f32>,
+        cx: &App,
+    ) -> Result<(), PaneGroupError> {
+        let Member::Axis(root) = &self.root else {
+            return Err(PaneGroupError::NoSplits);
+        };
+        let mut axis = root;
+        for ix in axis_path {
+            match axis.members.get(*ix) {
+                Some(Member::Axis(child)) => axis = child,
+                _ => return Err(PaneGroupError::SplitNotFound(axis_path.to_vec())),
+            }
+        }
+        axis.resize_divider(divider_index, delta, max_flex_ratio, cx)
+    }
+
+    /// The panes in the split `pane` is a direct member of, including `pane` and the panes
+    /// nested in sibling splits, in tree order. A group that is just `pane` returns it
+    /// alone, and a group without `pane` returns no panes.
+    ///
+    /// This scopes operations like equalizing sizes to one row or column of the layout.
+    pub fn panes_in_axis_of(&self, pane: &Entity<Pane>) -> Vec<&Entity<Pane>> {
+        let mut panes = Vec::new();
+        match &self.root {
+            Member::Pane(root) if root == pane => panes.push(root),
+            Member::Pane(_) => {}
+            Member::Axis(axis) => {
+                if let Some(axis) = axis.axis_containing(pane) {
+                    for member in &axis.members {
+                        member.collect_panes(&mut panes);
+                    }
+                }
+            }
+        }
+        panes
+    }
+
+    /// Move `pane` within its split to the slot nearest `position`, like dropping it onto
+    /// a sibling: onto the first half of a sibling places it before that sibling, onto the
+    /// second half after it.
+    ///
+    /// Returns `false` if `pane` isn't part of a split, `position` isn't over a sibling, or
+    /// the pane would end up where it already is.
+    pub fn move_pane(&mut self, pane: &Entity<Pane>, position: Point<Pixels>) -> bool {
+        let Some(axis) = self.axis_containing_mut(pane) else {
+            return false;
+        };
+        let Some(from) = axis.member_index(pane) else {
+            return false;
+        };
+        axis.drop_index(from, position)
+            .is_some_and(|to| axis.reorder_member(from, to))
+    }
+
+    /// Center `pane` within its split, like [`PaneAxis::center_member`].
+    pub fn center_pane(&mut self, pane: &Entity<Pane>, cx: &App) -> bool {
+        let Some(axis) = self.axis_containing_mut(pane) else {
+            return false;
+        };
+        axis.member_index(pane)
+            .is_some_and(|ix| axis.center_member(ix, cx))
+    }
+
+    /// Check the invariants of the split tree, to turn silent layout corruption into an
+    /// error describing what is wrong: every split has at least two members, one flex
+    /// and bounding box per member, flexes that add up to the number of members, and no
+    /// pane appears twice.
+    pub fn validate(&self) -> Result<(), PaneGroupError> {
+        let mut seen = HashSet::default();
+        match &self.root {
+            Member::Pane(pane) => {
+                seen.insert(pane.entity_id());
+                Ok(())
+            }
+            Member::Axis(axis) => axis.validate(&mut Vec::new(), &mut seen),
+        }
+    }
+
+    /// Lay the group out to fill `bounds` without rendering it, dividing the space by the
+    /// flexes and ignoring the dividers, so [`PaneGroup::bounding_box_for_pane`] and
+    /// [`PaneGroup::pane_at_pixel_position`] can be used in tests.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_bounds(&self, bounds: Bounds<Pixels>) {
+        if let Member::Axis(axis) = &self.root {
+            axis.set_bounds(bounds);
+        }
+    }
+
+    /// The axis `pane` is a direct member of, `None` if it isn't part of a split.
+    pub(crate) fn axis_containing_mut(&mut self, pane: &Entity<Pane>) -> Option<&mut PaneAxis> {
+        match &mut self.root {
+            Member::Pane(_) => None,
+            Member::Axis(axis) => axis.axis_containing_mut(pane),
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn render(
         &self,
         active_pane: &Entity<Pane>,
         zoomed: Option<&AnyWeakView>,
+        options: PaneGroupOptions,
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) -> impl IntoElement {
-        self.root.render(0, active_pane, zoomed, window, cx)
+        let options = PaneGroupOptions {
+            highlight_active_pane: options.highlight_active_pane
+                && matches!(self.root, Member::Axis(_)),
+            ..options
+        };
+        self.root
+            .render(0, active_pane, zoomed, options, window, cx)
+    }
+
+    pub(crate) fn serialize(&self, cx: &App) -> SerializedPaneGroup {
+        self.root.serialize(cx)
+    }
+
+    /// The panes of the group in tree order, to inspect layouts in tests.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn test_panes(&self) -> Vec<&Entity<Pane>> {
+        self.panes()
     }
 
     pub(crate) fn panes(&self) -> Vec<&Entity<Pane>> {
@@ -103,10 +437,34 @@ impl PaneGroup {
         panes
     }
 
+    /// Returns how deeply the splits are nested, a single pane has a depth of 0.
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    /// The smallest size the group can be laid out in without squeezing a pane below its
+    /// minimum size, see [`Pane::min_size`].
+    pub fn minimum_size(&self, cx: &App) -> Size<Pixels> {
+        self.root.minimum_size(cx)
+    }
+
+    pub fn pane_count(&self) -> usize {
+        self.panes().len()
+    }
+
     #[allow(unused)]
     pub(crate) fn first_pane(&self) -> Entity<Pane> {
         self.root.first_pane()
     }
+
+    /// Clone the group without sharing flexes and bounding boxes with `self`.
+    ///
+    /// A plain `clone` shares them, so resizing either group would resize both.
+    pub fn deep_clone(&self) -> PaneGroup {
+        PaneGroup {
+            root: self.root.deep_clone(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -116,7 +474,12 @@ pub(crate) enum Member {
 }
 
 impl Member {
-    fn new_axis(old_pane: Entity<Pane>, new_pane: Entity<Pane>, direction: SplitDirection) -> Self {
+    fn new_axis(
+        old_pane: Entity<Pane>,
+        new_pane: Entity<Pane>,
+        direction: SplitDirection,
+        ratio: f32,
+    ) -> Self {
         use Axis::*;
         use SplitDirection::*;
 
@@ -130,7 +493,13 @@ impl Member {
             Down | Right => vec![Member::Pane(old_pane), Member::Pane(new_pane)],
         };
 
-        Member::Axis(PaneAxis::new(axis, members))
+        let axis = PaneAxis::new(axis, members);
+        let (old_flex, new_flex) = (2. * (1. - ratio), 2. * ratio);
+        *axis.flexes.lock() = match direction {
+            Up | Left => vec![new_flex, old_flex],
+            Down | Right => vec![old_flex, new_flex],
+        };
+        Member::Axis(axis)
     }
 
     fn contains(&self, needle: &Entity<Pane>) -> bool {
@@ -140,6 +509,20 @@ impl Member {
         }
     }
 
+    fn replace(&mut self, old: &Entity<Pane>, new: Entity<Pane>) -> Result<(), PaneGroupError> {
+        match self {
+            Member::Axis(axis) => axis.replace(old, new),
+            Member::Pane(pane) => {
+                if pane == old {
+                    *pane = new;
+                    Ok(())
+                } else {
+                    Err(PaneGroupError::PaneNotFound)
+                }
+            }
+        }
+    }
+
     fn first_pane(&self) -> Entity<Pane> {
         match self {
             Member::Axis(axis) => axis.members[0].first_pane(),
@@ -147,12 +530,66 @@ impl Member {
         }
     }
 
+    fn depth(&self) -> usize {
+        match self {
+            Member::Axis(axis) => axis.depth(),
+            Member::Pane(_) => 0,
+        }
+    }
+
+    fn minimum_size(&self, cx: &App) -> Size<Pixels> {
+        match self {
+            Member::Axis(axis) => axis.minimum_size(cx),
+            Member::Pane(pane) => {
+                let pane = pane.read(cx);
+                size(
+                    pane.min_size(Axis::Horizontal),
+                    pane.min_size(Axis::Vertical),
+                )
+            }
+        }
+    }
+
+    fn deep_clone(&self) -> Member {
+        match self {
+            Member::Axis(axis) => Member::Axis(axis.deep_clone()),
+            Member::Pane(pane) => Member::Pane(pane.clone()),
+        }
+    }
+
+    fn serialize(&self, cx: &App) -> SerializedPaneGroup {
+        match self {
+            Member::Axis(axis) => SerializedPaneGroup::Group {
+                axis: axis.axis,
+                flexes: axis.flexes.lock().clone(),
+                children: axis
+                    .members
+                    .iter()
+                    .map(|member| member.serialize(cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => {
+                let pane_ref = pane.read(cx);
+                SerializedPaneGroup::Pane(SerializedPane {
+                    pane_id: pane_ref.serialized_id(),
+                    items: pane_ref
+                        .items()
+                        .map(|item| item.item_id().as_u64())
+                        .collect(),
+                    active_item_index: pane_ref.active_item_index(),
+                    zoomed: pane_ref.is_zoomed(),
+                })
+            }
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
         basis: usize,
         active_pane: &Entity<Pane>,
         zoomed: Option<&AnyWeakView>,
+        options: PaneGroupOptions,
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) -> impl IntoElement {
@@ -162,6 +599,12 @@ impl Member {
                     return div().into_any();
                 }
 
+                // The zoom overlay covers the whole center, skip laying out the hidden content.
+                // The workspace re-renders as soon as it zooms out, so there is no blank frame.
+                if zoomed.is_some() {
+                    return div().relative().flex_1().size_full().into_any();
+                }
+
                 div()
                     .relative()
                     .flex_1()
@@ -170,10 +613,23 @@ impl Member {
                         AnyView::from(pane.clone())
                             .cached(StyleRefinement::default().v_flex().size_full()),
                     )
+                    // Drawn over the pane so the outline doesn't change its size
+                    .when(
+                        options.highlight_active_pane && pane == active_pane,
+                        |this| {
+                            this.child(
+                                div()
+                                    .absolute()
+                                    .inset_0()
+                                    .border_1()
+                                    .border_color(cx.theme().ring.opacity(0.5)),
+                            )
+                        },
+                    )
                     .into_any()
             }
             Member::Axis(axis) => axis
-                .render(basis + 1, active_pane, zoomed, window, cx)
+                .render(basis + 1, active_pane, zoomed, options, window, cx)
                 .into_any(),
         }
     }
@@ -196,6 +652,11 @@ pub(crate) struct PaneAxis {
     pub members: Vec<Member>,
     pub flexes: Arc<Mutex<Vec<f32>>>,
     pub bounding_boxes: Arc<Mutex<Vec<Option<Bounds<Pixels>>>>>,
+    /// The flex collapsed members had before collapsing, by member index.
+    ///
+    /// Indices shift with the members and are cleared with [`PaneAxis::reset_flexes`], so
+    /// an entry never belongs to another member.
+    collapsed_flexes: HashMap<usize, f32>,
 }
 
 impl PaneAxis {
@@ -207,13 +668,15 @@ impl PaneAxis {
             members,
             flexes,
             bounding_boxes,
+            collapsed_flexes: HashMap::default(),
         }
     }
 
-    #[allow(unused)]
     pub fn load(axis: Axis, members: Vec<Member>, flexes: Option<Vec<f32>>) -> Self {
-        let flexes = flexes.unwrap_or_else(|| vec![1.; members.len()]);
-        debug_assert!(members.len() == flexes.len());
+        let flexes = flexes.map_or_else(
+            || vec![1.; members.len()],
+            |flexes| Self::normalize_flexes(flexes, members.len()),
+        );
 
         let flexes = Arc::new(Mutex::new(flexes));
         let bounding_boxes = Arc::new(Mutex::new(vec![None; members.len()]));
@@ -222,7 +685,338 @@ impl PaneAxis {
             members,
             flexes,
             bounding_boxes,
+            collapsed_flexes: HashMap::default(),
+        }
+    }
+
+    /// Repair flexes that don't fit `len` members, e.g. from a hand edited layout. Missing
+    /// or invalid flexes become `1.`, and all of them are scaled to add up to `len`.
+    fn normalize_flexes(mut flexes: Vec<f32>, len: usize) -> Vec<f32> {
+        flexes.resize(len, 1.);
+        for flex in &mut flexes {
+            if !flex.is_finite() || *flex < 0. {
+                *flex = 1.;
+            }
+        }
+
+        let total = flexes.iter().sum::<f32>();
+        if total > 0. {
+            for flex in &mut flexes {
+                *flex *= len as f32 / total;
+            }
+        } else {
+            flexes = vec![1.; len];
+        }
+        flexes
+    }
+
+    fn deep_clone(&self) -> PaneAxis {
+        PaneAxis {
+            axis: self.axis,
+            members: self.members.iter().map(Member::deep_clone).collect(),
+            flexes: Arc::new(Mutex::new(self.flexes.lock().clone())),
+            bounding_boxes: Arc::new(Mutex::new(self.bounding_boxes.lock().clone())),
+            collapsed_flexes: self.collapsed_flexes.clone(),
+        }
+    }
+
+    /// Resize the members around the one at `ix` so it sits in the middle of the axis,
+    /// keeping its size where the members on either side still fit their minimum size.
+    /// The members before it share one half of the remaining space, those after it the
+    /// other.
+    ///
+    /// Returns `false` if the member is collapsed or there are no expanded members on one
+    /// of its sides, since it can't be centered then.
+    pub fn center_member(&mut self, ix: usize, cx: &App) -> bool {
+        let min_sizes = self.member_min_sizes(cx);
+        // Only known once the split has been laid out, until then minimums are ignored
+        let container_size = self
+            .bounding_boxes
+            .lock()
+            .iter()
+            .try_fold(px(0.), |size, bounds| {
+                Some(size + bounds.as_ref()?.size.along(self.axis))
+            });
+
+        let mut flexes = self.flexes.lock();
+        let len = flexes.len();
+        if ix >= len || flexes[ix] == 0. {
+            return false;
+        }
+        let before = (0..ix).filter(|ix| flexes[*ix] > 0.).count();
+        let after = (ix + 1..len).filter(|ix| flexes[*ix] > 0.).count();
+        if before == 0 || after == 0 {
+            return false;
+        }
+
+        // Each side shares its space evenly, so it needs its largest minimum for every member
+        let total = len as f32;
+        let side_min_flex = |members: std::ops::Range<usize>, count: usize| {
+            let largest_min = members
+                .filter(|ix| flexes[*ix] > 0.)
+                .fold(px(0.), |largest, ix| largest.max(min_sizes[ix]));
+            container_size
+                .filter(|size| *size > px(0.))
+                .map_or(0., |size| largest_min / size * total * count as f32)
+        };
+        let max_flex =
+            total - 2. * side_min_flex(0..ix, before).max(side_min_flex(ix + 1..len, after));
+        let flex = flexes[ix].min(max_flex);
+        if flex <= 0. {
+            return false;
+        }
+
+        let side_flex = (total - flex) / 2.;
+        for (member_ix, member_flex) in flexes.iter_mut().enumerate() {
+            if member_ix == ix {
+                *member_flex = flex;
+            } else if *member_flex > 0. {
+                let side_count = if member_ix < ix { before } else { after };
+                *member_flex = side_flex / side_count as f32;
+            }
+        }
+        true
+    }
+
+    /// The minimum size of each member along the axis.
+    fn member_min_sizes(&self, cx: &App) -> Vec<Pixels> {
+        self.members
+            .iter()
+            .map(|member| member.minimum_size(cx).along(self.axis))
+            .collect()
+    }
+
+    fn resize_divider(
+        &self,
+        divider_index: usize,
+        delta: Pixels,
+        max_flex_ratio: Option<f32>,
+        cx: &App,
+    ) -> Result<(), PaneGroupError> {
+        if divider_index + 1 >= self.members.len() {
+            return Err(PaneGroupError::DividerNotFound(divider_index));
+        }
+        let container_size = self
+            .bounding_boxes
+            .lock()
+            .iter()
+            .try_fold(px(0.), |size, bounds| {
+                Some(size + bounds.as_ref()?.size.along(self.axis))
+            })
+            .ok_or(PaneGroupError::NotLaidOut)?;
+
+        let min_sizes = self.member_min_sizes(cx);
+        let mut flexes = self.flexes.lock();
+        if !resize_flexes(
+            flexes.as_mut_slice(),
+            divider_index,
+            &min_sizes,
+            container_size,
+            max_flex_ratio,
+            delta,
+        ) {
+            return Err(PaneGroupError::DividerImmovable(divider_index));
+        }
+        Ok(())
+    }
+
+    fn member_index(&self, pane: &Entity<Pane>) -> Option<usize> {
+        self.members
+            .iter()
+            .position(|member| matches!(member, Member::Pane(found) if found == pane))
+    }
+
+    /// Shrink the member at `ix` to a thin strip, remembering its flex for
+    /// [`PaneAxis::expand_member`]. The other members grow to fill the freed space.
+    pub fn collapse_member(&mut self, ix: usize) -> bool {
+        let mut flexes = self.flexes.lock();
+        let expanded_count = flexes.iter().filter(|flex| **flex > 0.).count();
+        if ix >= flexes.len() || flexes[ix] == 0. || expanded_count < 2 {
+            return false;
+        }
+
+        let len = flexes.len() as f32;
+        let previous_flex = flexes[ix];
+        let scale = len / (len - previous_flex);
+        for flex in flexes.iter_mut() {
+            *flex *= scale;
+        }
+        flexes[ix] = 0.;
+        self.collapsed_flexes.insert(ix, previous_flex);
+        true
+    }
+
+    /// Restore the member at `ix` to the flex it had before [`PaneAxis::collapse_member`],
+    /// shrinking the other members proportionally.
+    pub fn expand_member(&mut self, ix: usize) -> bool {
+        let mut flexes = self.flexes.lock();
+        if ix >= flexes.len() || flexes[ix] != 0. {
+            return false;
+        }
+
+        let len = flexes.len() as f32;
+        // Serialized layouts can contain collapsed members without a remembered flex.
+        let restored_flex = self.collapsed_flexes.remove(&ix).unwrap_or(1.);
+        let scale = (len - restored_flex) / len;
+        for flex in flexes.iter_mut() {
+            *flex *= scale;
         }
+        flexes[ix] = restored_flex;
+        true
+    }
+
+    /// Move the member at `from` to `to`, shifting the members in between over by one.
+    ///
+    /// Unlike [`PaneAxis::swap`], the order of the other members is kept. Every member
+    /// keeps its flex, so sizes travel with the members.
+    pub fn reorder_member(&mut self, from: usize, to: usize) -> bool {
+        let len = self.members.len();
+        if from >= len || to >= len || from == to {
+            return false;
+        }
+
+        let member = self.members.remove(from);
+        self.members.insert(to, member);
+        let mut flexes = self.flexes.lock();
+        let flex = flexes.remove(from);
+        flexes.insert(to, flex);
+        // Splits don't track bounding boxes until the next layout, so they can be missing.
+        let mut bounding_boxes = self.bounding_boxes.lock();
+        if bounding_boxes.len() == len {
+            let bounding_box = bounding_boxes.remove(from);
+            bounding_boxes.insert(to, bounding_box);
+        } else {
+            *bounding_boxes = vec![None; len];
+        }
+
+        let shifted = |ix: usize| {
+            if ix == from {
+                to
+            } else if from < ix && ix <= to {
+                ix - 1
+            } else if to <= ix && ix < from {
+                ix + 1
+            } else {
+                ix
+            }
+        };
+        self.collapsed_flexes = mem::take(&mut self.collapsed_flexes)
+            .into_iter()
+            .map(|(ix, flex)| (shifted(ix), flex))
+            .collect();
+        true
+    }
+
+    /// Add `member` at `ix` with the average flex, so the members already in the axis keep
+    /// their share of each other's space.
+    fn insert_member(&mut self, ix: usize, member: Member) {
+        self.members.insert(ix, member);
+        self.flexes.lock().insert(ix, 1.);
+        *self.bounding_boxes.lock() = vec![None; self.members.len()];
+        self.collapsed_flexes = mem::take(&mut self.collapsed_flexes)
+            .into_iter()
+            .map(|(collapsed_ix, flex)| {
+                if collapsed_ix >= ix {
+                    (collapsed_ix + 1, flex)
+                } else {
+                    (collapsed_ix, flex)
+                }
+            })
+            .collect();
+    }
+
+    /// The index the member at `from` should move to when dropped at `position`, checked
+    /// against the laid out bounds of its siblings. `None` if `position` isn't over a
+    /// sibling or the member would stay put.
+    fn drop_index(&self, from: usize, position: Point<Pixels>) -> Option<usize> {
+        let bounding_boxes = self.bounding_boxes.lock();
+        let (target, bounds) = bounding_boxes.iter().enumerate().find_map(|(ix, bounds)| {
+            bounds
+                .filter(|bounds| bounds.contains(&position))
+                .map(|bounds| (ix, bounds))
+        })?;
+        if target == from {
+            return None;
+        }
+
+        let before = position.along(self.axis) < bounds.center().along(self.axis);
+        let slot = if before { target } else { target + 1 };
+        let to = if slot > from { slot - 1 } else { slot };
+        (to != from).then_some(to)
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    fn set_bounds(&self, bounds: Bounds<Pixels>) {
+        let flexes = self.flexes.lock().clone();
+        let total_flex = flexes.len() as f32;
+        let total_length = bounds.size.along(self.axis);
+        let mut bounding_boxes = self.bounding_boxes.lock();
+        bounding_boxes.clear();
+
+        let mut origin = bounds.origin;
+        for (member, flex) in self.members.iter().zip(flexes) {
+            let length = total_length * (flex / total_flex);
+            let mut size = bounds.size;
+            match self.axis {
+                Axis::Horizontal => size.width = length,
+                Axis::Vertical => size.height = length,
+            }
+            let member_bounds = Bounds { origin, size };
+            bounding_boxes.push(Some(member_bounds));
+            if let Member::Axis(axis) = member {
+                axis.set_bounds(member_bounds);
+            }
+            match self.axis {
+                Axis::Horizontal => origin.x += length,
+                Axis::Vertical => origin.y += length,
+            }
+        }
+    }
+
+    fn validate(
+        &self,
+        path: &mut Vec<usize>,
+        seen: &mut HashSet<EntityId>,
+    ) -> Result<(), PaneGroupError> {
+        let invalid = |description: String, path: &[usize]| {
+            Err(PaneGroupError::InvalidLayout(format!(
+                "{description} in split {path:?}"
+            )))
+        };
+        let len = self.members.len();
+        if len < 2 {
+            return invalid(format!("split with {len} members"), path);
+        }
+        let flexes = self.flexes.lock().clone();
+        if flexes.len() != len {
+            return invalid(format!("{} flexes for {len} members", flexes.len()), path);
+        }
+        let bounding_box_count = self.bounding_boxes.lock().len();
+        if bounding_box_count != len {
+            return invalid(
+                format!("{bounding_box_count} bounding boxes for {len} members"),
+                path,
+            );
+        }
+        if !flex_values_in_bounds(&flexes) {
+            return invalid(format!("flexes {flexes:?} don't add up to {len}"), path);
+        }
+
+        for (ix, member) in self.members.iter().enumerate() {
+            match member {
+                Member::Pane(pane) => {
+                    if !seen.insert(pane.entity_id()) {
+                        return invalid(format!("pane {} appearing twice", pane.entity_id()), path);
+                    }
+                }
+                Member::Axis(axis) => {
+                    path.push(ix);
+                    axis.validate(path, seen)?;
+                    path.pop();
+                }
+            }
+        }
+        Ok(())
     }
 
     fn split(
@@ -230,36 +1024,59 @@ impl PaneAxis {
         old_pane: &Entity<Pane>,
         new_pane: &Entity<Pane>,
         direction: SplitDirection,
-    ) -> Result<()> {
+        ratio: f32,
+    ) -> Result<(), PaneGroupError> {
         for (mut idx, member) in self.members.iter_mut().enumerate() {
             match member {
                 Member::Axis(axis) => {
-                    if axis.split(old_pane, new_pane, direction).is_ok() {
+                    if axis.split(old_pane, new_pane, direction, ratio).is_ok() {
                         return Ok(());
                     }
                 }
                 Member::Pane(pane) => {
                     if pane == old_pane {
                         if direction.axis() == self.axis {
+                            let old_ix = idx;
                             if direction.increasing() {
                                 idx += 1;
                             }
 
+                            // A collapsed pane has no space to share
+                            self.expand_member(old_ix);
                             self.members.insert(idx, Member::Pane(new_pane.clone()));
-                            *self.flexes.lock() = vec![1.; self.members.len()];
+                            // Take the new pane's share from the pane being split only, so the
+                            // other members keep their sizes. Rescale so the flexes still add
+                            // up to the number of members.
+                            let mut flexes = self.flexes.lock();
+                            let old_flex = flexes[old_ix];
+                            flexes[old_ix] = old_flex * (1. - ratio);
+                            flexes.insert(idx, old_flex * ratio);
+                            self.bounding_boxes.lock().insert(idx, None);
+                            let scale = flexes.len() as f32 / (flexes.len() - 1) as f32;
+                            for flex in flexes.iter_mut() {
+                                *flex *= scale;
+                            }
+                            self.collapsed_flexes = mem::take(&mut self.collapsed_flexes)
+                                .into_iter()
+                                .map(|(ix, flex)| (if ix >= idx { ix + 1 } else { ix }, flex))
+                                .collect();
                         } else {
-                            *member =
-                                Member::new_axis(old_pane.clone(), new_pane.clone(), direction);
+                            *member = Member::new_axis(
+                                old_pane.clone(),
+                                new_pane.clone(),
+                                direction,
+                                ratio,
+                            );
                         }
                         return Ok(());
                     }
                 }
             }
         }
-        Err(anyhow!("Pane not found"))
+        Err(PaneGroupError::PaneNotFound)
     }
 
-    fn remove(&mut self, pane_to_remove: &Entity<Pane>) -> Result<Option<Member>> {
+    fn remove(&mut self, pane_to_remove: &Entity<Pane>) -> Result<Option<Member>, PaneGroupError> {
         let mut found_pane = false;
         let mut remove_member = None;
         for (idx, member) in self.members.iter_mut().enumerate() {
@@ -286,21 +1103,61 @@ impl PaneAxis {
         if found_pane {
             if let Some(idx) = remove_member {
                 self.members.remove(idx);
-                *self.flexes.lock() = vec![1.; self.members.len()];
+                self.reset_flexes();
             }
 
             if self.members.len() == 1 {
                 let result = self.members.pop();
-                *self.flexes.lock() = vec![1.; self.members.len()];
+                self.reset_flexes();
                 Ok(result)
             } else {
                 Ok(None)
             }
         } else {
-            Err(anyhow!("Pane not found"))
+            Err(PaneGroupError::PaneNotFound)
         }
     }
 
+    /// Members are laid out next to each other along the axis, so their minimums add up
+    /// along it, while across it the largest minimum wins.
+    fn minimum_size(&self, cx: &App) -> Size<Pixels> {
+        let flexes = self.flexes.lock();
+        self.members
+            .iter()
+            .enumerate()
+            .fold(Size::default(), |total, (ix, member)| {
+                let mut member_size = member.minimum_size(cx);
+                if flexes.get(ix) == Some(&0.) {
+                    member_size = member_size.apply_along(self.axis, |_| px(COLLAPSED_MEMBER_SIZE));
+                }
+                let along = total.along(self.axis) + member_size.along(self.axis);
+                let across = total
+                    .along(self.axis.invert())
+                    .max(member_size.along(self.axis.invert()));
+                Size::default()
+                    .apply_along(self.axis, |_| along)
+                    .apply_along(self.axis.invert(), |_| across)
+            })
+    }
+
+    fn depth(&self) -> usize {
+        1 + self
+            .members
+            .iter()
+            .map(|member| member.depth())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn replace(&mut self, old: &Entity<Pane>, new: Entity<Pane>) -> Result<(), PaneGroupError> {
+        let member = self
+            .members
+            .iter_mut()
+            .find(|member| member.contains(old))
+            .ok_or(PaneGroupError::PaneNotFound)?;
+        member.replace(old, new)
+    }
+
     fn swap(&mut self, from: &Entity<Pane>, to: &Entity<Pane>) {
         for member in self.members.iter_mut() {
             match member {
@@ -316,6 +1173,51 @@ impl PaneAxis {
         }
     }
 
+    fn axis_containing(&self, pane: &Entity<Pane>) -> Option<&PaneAxis> {
+        if self
+            .members
+            .iter()
+            .any(|member| matches!(member, Member::Pane(found) if found == pane))
+        {
+            return Some(self);
+        }
+
+        self.members.iter().find_map(|member| match member {
+            Member::Axis(axis) => axis.axis_containing(pane),
+            Member::Pane(_) => None,
+        })
+    }
+
+    fn axis_containing_mut(&mut self, pane: &Entity<Pane>) -> Option<&mut PaneAxis> {
+        if self
+            .members
+            .iter()
+            .any(|member| matches!(member, Member::Pane(found) if found == pane))
+        {
+            return Some(self);
+        }
+
+        self.members.iter_mut().find_map(|member| match member {
+            Member::Axis(axis) => axis.axis_containing_mut(pane),
+            Member::Pane(_) => None,
+        })
+    }
+
+    fn rotate(&mut self) {
+        self.axis = match self.axis {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        };
+        self.reset_flexes();
+    }
+
+    /// Give every member the same size again, forgetting the sizes of collapsed members.
+    fn reset_flexes(&mut self) {
+        *self.flexes.lock() = vec![1.; self.members.len()];
+        *self.bounding_boxes.lock() = vec![None; self.members.len()];
+        self.collapsed_flexes.clear();
+    }
+
     fn bounding_box_for_pane(&self, pane: &Entity<Pane>) -> Option<Bounds<Pixels>> {
         debug_assert!(self.members.len() == self.bounding_boxes.lock().len());
 
@@ -360,6 +1262,7 @@ impl PaneAxis {
         basis: usize,
         active_pane: &Entity<Pane>,
         zoomed: Option<&AnyWeakView>,
+        options: PaneGroupOptions,
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) -> gpui::AnyElement {
@@ -371,6 +1274,8 @@ impl PaneAxis {
             basis,
             self.flexes.clone(),
             self.bounding_boxes.clone(),
+            self.member_min_sizes(cx),
+            options,
             cx.entity().downgrade(),
         )
         .children(self.members.iter().enumerate().map(|(ix, member)| {
@@ -378,7 +1283,7 @@ impl PaneAxis {
                 active_pane_ix = Some(ix);
             }
             member
-                .render((basis + ix) * 10, active_pane, zoomed, window, cx)
+                .render((basis + ix) * 10, active_pane, zoomed, options, window, cx)
                 .into_any_element()
         }))
         .with_active_pane(active_pane_ix)
@@ -453,6 +1358,15 @@ impl SplitDirection {
             Self::Down | Self::Right => true,
         }
     }
+
+    pub fn opposite(&self) -> SplitDirection {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
 }
 
 mod element {
@@ -462,9 +1376,9 @@ mod element {
 
     use crate::util::ResultExt;
     use gpui::{
-        px, relative, Along, AnyElement, App, Axis, Bounds, Element, ElementId, GlobalElementId,
-        IntoElement, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point,
-        Size, Style, WeakEntity,
+        div, point, px, relative, Along, AnyElement, App, AvailableSpace, Axis, Bounds, Element,
+        ElementId, GlobalElementId, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent,
+        MouseUpEvent, ParentElement, Pixels, Point, Size, Style, Styled as _, WeakEntity,
     };
     use gpui::{CursorStyle, Hitbox};
     use parking_lot::Mutex;
@@ -474,15 +1388,15 @@ mod element {
 
     use crate::Workspace;
 
-    use super::{HANDLE_HITBOX_SIZE, HORIZONTAL_MIN_SIZE, VERTICAL_MIN_SIZE};
-
-    const DIVIDER_SIZE: f32 = 1.0;
+    use super::{PaneGroupOptions, COLLAPSED_MEMBER_SIZE};
 
     pub(super) fn pane_axis(
         axis: Axis,
         basis: usize,
         flexes: Arc<Mutex<Vec<f32>>>,
         bounding_boxes: Arc<Mutex<Vec<Option<Bounds<Pixels>>>>>,
+        min_sizes: Vec<Pixels>,
+        options: PaneGroupOptions,
         workspace: WeakEntity<Workspace>,
     ) -> PaneAxisElement {
         PaneAxisElement {
@@ -490,17 +1404,125 @@ mod element {
             basis,
             flexes,
             bounding_boxes,
+            min_sizes,
+            options,
             children: SmallVec::new(),
             active_pane_ix: None,
             workspace,
         }
     }
 
+    /// Move the divider after the member at `ix` by `delta`, cascading into further members
+    /// once the neighbours reach their minimum size. `min_sizes` are the minimum sizes of
+    /// the members and `container_size` the length of the axis the flexes are laid out along.
+    ///
+    /// Returns `false` if the divider can't be moved, leaving the flexes unchanged.
+    pub(super) fn resize_flexes(
+        flexes: &mut [f32],
+        ix: usize,
+        min_sizes: &[Pixels],
+        container_size: Pixels,
+        max_flex_ratio: Option<f32>,
+        delta: Pixels,
+    ) -> bool {
+        debug_assert!(flexes.len() == min_sizes.len());
+        debug_assert!(flex_values_in_bounds(flexes));
+
+        let size = move |ix, flexes: &[f32]| container_size * (flexes[ix] / flexes.len() as f32);
+        let max_size =
+            max_flex_ratio.map(|ratio| container_size * ratio.max(1. / flexes.len() as f32));
+
+        // Collapsed members keep their size until they are expanded
+        if flexes[ix] == 0. || flexes[ix + 1] == 0. {
+            return false;
+        }
+
+        // Don't allow resizing to less than the minimum size, if elements are already too small
+        if min_sizes[ix] - px(1.) > size(ix, flexes) {
+            return false;
+        }
+
+        let mut proposed_current_pixel_change = delta;
+
+        let flex_changes = |pixel_dx, target_ix, next: isize, flexes: &[f32]| {
+            let flex_change = pixel_dx / container_size;
+            let current_target_flex = flexes[target_ix] + flex_change;
+            let next_target_flex = flexes[(target_ix as isize + next) as usize] - flex_change;
+            (current_target_flex, next_target_flex)
+        };
+
+        let mut successors = iter::from_fn({
+            let forward = proposed_current_pixel_change > px(0.);
+            let mut ix_offset = 0;
+            let len = flexes.len();
+            move || {
+                let result = if forward {
+                    (ix + 1 + ix_offset < len).then(|| ix + ix_offset)
+                } else {
+                    (ix as isize - ix_offset as isize >= 0).then(|| ix - ix_offset)
+                };
+
+                ix_offset += 1;
+
+                result
+            }
+        });
+
+        while proposed_current_pixel_change.abs() > px(0.) {
+            let Some(current_ix) = successors.next() else {
+                break;
+            };
+            if flexes[current_ix] == 0. || flexes[current_ix + 1] == 0. {
+                break;
+            }
+
+            // Once either side of the divider reaches the maximum size, stop cascading the
+            // remaining change into further members.
+            let mut reached_max_size = false;
+            let mut clamp_to_max = |target_size: Pixels| match max_size {
+                Some(max_size) if target_size > max_size => {
+                    reached_max_size = true;
+                    max_size
+                }
+                _ => target_size,
+            };
+
+            // Each member of the pair is held at its own minimum
+            let next_target_size = clamp_to_max(Pixels::max(
+                size(current_ix + 1, flexes) - proposed_current_pixel_change,
+                min_sizes[current_ix + 1],
+            ));
+
+            let current_target_size = clamp_to_max(Pixels::max(
+                size(current_ix, flexes) + size(current_ix + 1, flexes) - next_target_size,
+                min_sizes[current_ix],
+            ));
+
+            let current_pixel_change = current_target_size - size(current_ix, flexes);
+
+            let (current_target_flex, next_target_flex) =
+                flex_changes(current_pixel_change, current_ix, 1, flexes);
+
+            flexes[current_ix] = current_target_flex;
+            flexes[current_ix + 1] = next_target_flex;
+
+            proposed_current_pixel_change -= current_pixel_change;
+
+            if reached_max_size {
+                break;
+            }
+        }
+        true
+    }
+
     pub struct PaneAxisElement {
         axis: Axis,
         basis: usize,
         flexes: Arc<Mutex<Vec<f32>>>,
         bounding_boxes: Arc<Mutex<Vec<Option<Bounds<Pixels>>>>>,
+        /// The minimum size of each member along the axis.
+        min_sizes: Vec<Pixels>,
+        options: PaneGroupOptions,
         children: SmallVec<[AnyElement; 2]>,
         active_pane_ix: Option<usize>,
         workspace: WeakEntity<Workspace>,
@@ -520,6 +1542,8 @@ mod element {
     struct PaneAxisHandleLayout {
         hitbox: Hitbox,
         divider_bounds: Bounds<Pixels>,
+        /// Shows the sizes of the members next to the handle while it's hovered or dragged.
+        ratio_label: Option<AnyElement>,
     }
 
     impl PaneAxisElement {
@@ -531,120 +1555,168 @@ mod element {
         #[allow(clippy::too_many_arguments)]
         fn compute_resize(
             flexes: &Arc<Mutex<Vec<f32>>>,
+            min_sizes: &[Pixels],
             e: &MouseMoveEvent,
             ix: usize,
             axis: Axis,
             child_start: Point<Pixels>,
             container_size: Size<Pixels>,
+            max_flex_ratio: Option<f32>,
             workspace: WeakEntity<Workspace>,
             window: &mut Window,
             cx: &mut App,
         ) {
-            let min_size = match axis {
-                Axis::Horizontal => px(HORIZONTAL_MIN_SIZE),
-                Axis::Vertical => px(VERTICAL_MIN_SIZE),
-            };
             let mut flexes = flexes.lock();
-            debug_assert!(flex_values_in_bounds(flexes.as_slice()));
+            let container_size = container_size.along(axis);
+            let current_size = container_size * (flexes[ix] / flexes.len() as f32);
+            let resized = resize_flexes(
+                flexes.as_mut_slice(),
+                ix,
+                min_sizes,
+                container_size,
+                max_flex_ratio,
+                (e.position - child_start).along(axis) - current_size,
+            );
+            drop(flexes);
+            if !resized {
+                return;
+            }
 
-            let size = move |ix, flexes: &[f32]| {
-                container_size.along(axis) * (flexes[ix] / flexes.len() as f32)
-            };
+            workspace
+                .update(cx, |this, cx| this.pane_sizes_changed(window, cx))
+                .log_err();
+            cx.stop_propagation();
+            window.refresh();
+        }
 
-            // Don't allow resizing to less than the minimum size, if elements are already too small
-            if min_size - px(1.) > size(ix, flexes.as_slice()) {
+        /// Shrink the smaller of the two members around the divider at `ix` to the minimum
+        /// size, giving the freed space to the other one.
+        fn collapse_smaller_member(
+            flexes: &Arc<Mutex<Vec<f32>>>,
+            min_sizes: &[Pixels],
+            ix: usize,
+            axis: Axis,
+            container_size: Size<Pixels>,
+        ) {
+            let mut flexes = flexes.lock();
+            if ix + 1 >= flexes.len() {
                 return;
             }
 
-            let mut proposed_current_pixel_change =
-                (e.position - child_start).along(axis) - size(ix, flexes.as_slice());
-
-            let flex_changes = |pixel_dx, target_ix, next: isize, flexes: &[f32]| {
-                let flex_change = pixel_dx / container_size.along(axis);
-                let current_target_flex = flexes[target_ix] + flex_change;
-                let next_target_flex = flexes[(target_ix as isize + next) as usize] - flex_change;
-                (current_target_flex, next_target_flex)
+            let (smaller_ix, larger_ix) = if flexes[ix] <= flexes[ix + 1] {
+                (ix, ix + 1)
+            } else {
+                (ix + 1, ix)
             };
+            let min_flex = min_sizes[smaller_ix] / container_size.along(axis) * flexes.len() as f32;
+            let freed_flex = flexes[smaller_ix] - min_flex;
+            if freed_flex > 0. {
+                flexes[smaller_ix] = min_flex;
+                flexes[larger_ix] += freed_flex;
+            }
+            debug_assert!(flex_values_in_bounds(flexes.as_slice()));
+        }
 
-            let mut successors = iter::from_fn({
-                let forward = proposed_current_pixel_change > px(0.);
-                let mut ix_offset = 0;
-                let len = flexes.len();
-                move || {
-                    let result = if forward {
-                        (ix + 1 + ix_offset < len).then(|| ix + ix_offset)
-                    } else {
-                        (ix as isize - ix_offset as isize >= 0).then(|| ix - ix_offset)
-                    };
-
-                    ix_offset += 1;
-
-                    result
-                }
-            });
-
-            while proposed_current_pixel_change.abs() > px(0.) {
-                let Some(current_ix) = successors.next() else {
-                    break;
-                };
-
-                let next_target_size = Pixels::max(
-                    size(current_ix + 1, flexes.as_slice()) - proposed_current_pixel_change,
-                    min_size,
-                );
-
-                let current_target_size = Pixels::max(
-                    size(current_ix, flexes.as_slice()) + size(current_ix + 1, flexes.as_slice())
-                        - next_target_size,
-                    min_size,
-                );
-
-                let current_pixel_change =
-                    current_target_size - size(current_ix, flexes.as_slice());
-
-                let (current_target_flex, next_target_flex) =
-                    flex_changes(current_pixel_change, current_ix, 1, flexes.as_slice());
-
-                flexes[current_ix] = current_target_flex;
-                flexes[current_ix + 1] = next_target_flex;
+        /// Grow the members smaller than their minimum size, the matching entry of
+        /// `min_sizes`, to that minimum, taking the space from the other members in
+        /// proportion to how far they are above theirs.
+        ///
+        /// Flexes can't express this since the minimum depends on the container size, it is
+        /// only enforced while resizing. When the container can't fit every member at its
+        /// minimum size, all members get an even share instead.
+        fn clamp_to_min_size(
+            available: Pixels,
+            sizes: Vec<Pixels>,
+            min_sizes: Vec<Pixels>,
+        ) -> Vec<Pixels> {
+            if sizes
+                .iter()
+                .zip(&min_sizes)
+                .all(|(size, min_size)| size >= min_size)
+            {
+                return sizes;
+            }
 
-                proposed_current_pixel_change -= current_pixel_change;
+            let total_min_size = min_sizes.iter().fold(px(0.), |total, size| total + *size);
+            if available < total_min_size {
+                let len = sizes.len() as f32;
+                return vec![available / len; sizes.len()];
             }
 
-            workspace
-                .update(cx, |this, cx| this.serialize_workspace(window, cx))
-                .log_err();
-            cx.stop_propagation();
-            window.refresh();
+            let excess = sizes
+                .iter()
+                .zip(&min_sizes)
+                .fold(px(0.), |excess, (size, min_size)| {
+                    excess + (*size - *min_size).max(px(0.))
+                });
+            let shrink = (available - total_min_size) / excess;
+            sizes
+                .into_iter()
+                .zip(min_sizes)
+                .map(|(size, min_size)| min_size + (size - min_size).max(px(0.)) * shrink)
+                .collect()
         }
 
         #[allow(clippy::too_many_arguments)]
         fn layout_handle(
             axis: Axis,
             pane_bounds: Bounds<Pixels>,
+            handle_hitbox_size: Pixels,
+            divider_size: Pixels,
             window: &mut Window,
             _: &mut App,
         ) -> PaneAxisHandleLayout {
             let handle_bounds = Bounds {
                 origin: pane_bounds.origin.apply_along(axis, |origin| {
-                    origin + pane_bounds.size.along(axis) - px(HANDLE_HITBOX_SIZE / 2.)
+                    origin + pane_bounds.size.along(axis) - handle_hitbox_size / 2.
                 }),
-                size: pane_bounds
-                    .size
-                    .apply_along(axis, |_| px(HANDLE_HITBOX_SIZE)),
+                size: pane_bounds.size.apply_along(axis, |_| handle_hitbox_size),
             };
             let divider_bounds = Bounds {
                 origin: pane_bounds
                     .origin
                     .apply_along(axis, |origin| origin + pane_bounds.size.along(axis)),
-                size: pane_bounds.size.apply_along(axis, |_| px(DIVIDER_SIZE)),
+                size: pane_bounds.size.apply_along(axis, |_| divider_size),
             };
 
             PaneAxisHandleLayout {
                 hitbox: window.insert_hitbox(handle_bounds, true),
                 divider_bounds,
+                ratio_label: None,
             }
         }
+
+        /// A label like "60% / 40%" with the sizes of the members at `ix` and `ix + 1`,
+        /// centered on the divider between them.
+        fn layout_ratio_label(
+            flexes: &[f32],
+            ix: usize,
+            divider_bounds: Bounds<Pixels>,
+            window: &mut Window,
+            cx: &mut App,
+        ) -> AnyElement {
+            let pair = flexes[ix] + flexes[ix + 1];
+            let first = if pair > 0. {
+                (flexes[ix] / pair * 100.).round()
+            } else {
+                50.
+            };
+
+            let mut label = div()
+                .px_1()
+                .rounded_sm()
+                .border_1()
+                .border_color(cx.theme().border)
+                .bg(cx.theme().background)
+                .text_xs()
+                .text_color(cx.theme().foreground)
+                .child(format!("{first}% / {}%", 100. - first))
+                .into_any_element();
+            let size = label.layout_as_root(AvailableSpace::min_size(), window, cx);
+            let origin = divider_bounds.center() - point(size.width / 2., size.height / 2.);
+            label.prepaint_at(origin, window, cx);
+            label
+        }
     }
 
     impl IntoElement for PaneAxisElement {
@@ -713,7 +1785,54 @@ mod element {
             };
 
             let mut origin = bounds.origin;
-            let space_per_flex = bounds.size.along(self.axis) / total_flex;
+            // Collapsed members get a fixed size, the others share the rest of the axis.
+            let collapsed = (0..len)
+                .map(|ix| active_pane_magnification.is_none() && flexes[ix] == 0.)
+                .collect::<Vec<_>>();
+            let collapsed_size = px(COLLAPSED_MEMBER_SIZE);
+            let collapsed_count = collapsed.iter().filter(|collapsed| **collapsed).count();
+            let available = (bounds.size.along(self.axis)
+                - collapsed_size * collapsed_count as f32)
+                .max(px(0.));
+            let space_per_flex = available / total_flex;
+            let mut expanded_sizes = Self::clamp_to_min_size(
+                available,
+                (0..len)
+                    .filter(|ix| !collapsed[*ix])
+                    .map(|ix| {
+                        let child_flex = active_pane_magnification
+                            .map(|magnification| {
+                                if self.active_pane_ix == Some(ix) {
+                                    magnification
+                                } else {
+                                    1.
+                                }
+                            })
+                            .unwrap_or_else(|| flexes[ix]);
+                        space_per_flex * child_flex
+                    })
+                    .collect(),
+                (0..len)
+                    .filter(|ix| !collapsed[*ix])
+                    .map(|ix| {
+                        self.min_sizes
+                            .get(ix)
+                            .copied()
+                            .unwrap_or_else(|| super::default_min_size(self.axis))
+                    })
+                    .collect(),
+            )
+            .into_iter();
+            let child_sizes = collapsed
+                .iter()
+                .map(|collapsed| {
+                    if *collapsed {
+                        collapsed_size
+                    } else {
+                        expanded_sizes.next().unwrap_or_default()
+                    }
+                })
+                .collect::<Vec<_>>();
 
             let mut bounding_boxes = self.bounding_boxes.lock();
             bounding_boxes.clear();
@@ -723,19 +1842,9 @@ mod element {
                 children: Vec::new(),
             };
             for (ix, mut child) in mem::take(&mut self.children).into_iter().enumerate() {
-                let child_flex = active_pane_magnification
-                    .map(|magnification| {
-                        if self.active_pane_ix == Some(ix) {
-                            magnification
-                        } else {
-                            1.
-                        }
-                    })
-                    .unwrap_or_else(|| flexes[ix]);
-
                 let child_size = bounds
                     .size
-                    .apply_along(self.axis, |_| space_per_flex * child_flex)
+                    .apply_along(self.axis, |_| child_sizes[ix])
                     .map(|d| d.round());
 
                 let child_bounds = Bounds {
@@ -761,6 +1870,30 @@ mod element {
                         child_layout.handle = Some(Self::layout_handle(
                             self.axis,
                             child_layout.bounds,
+                            self.options.handle_hitbox_size,
+                            self.options.divider_size,
+                            window,
+                            cx,
+                        ));
+                    }
+                }
+            }
+
+            let layout_locked = self
+                .workspace
+                .upgrade()
+                .is_some_and(|workspace| workspace.read(cx).layout_locked());
+            let dragged_ix = *dragged_handle.borrow();
+            if !layout_locked {
+                for (ix, child_layout) in layout.children.iter_mut().enumerate() {
+                    let Some(handle) = child_layout.handle.as_mut() else {
+                        continue;
+                    };
+                    if dragged_ix == Some(ix) || handle.hitbox.is_hovered(window) {
+                        handle.ratio_label = Some(Self::layout_ratio_label(
+                            &flexes,
+                            ix,
+                            handle.divider_bounds,
                             window,
                             cx,
                         ));
@@ -784,15 +1917,27 @@ mod element {
                 child.element.paint(window, cx);
             }
 
+            let layout_locked = self
+                .workspace
+                .upgrade()
+                .is_some_and(|workspace| workspace.read(cx).layout_locked());
+
             for (ix, child) in &mut layout.children.iter_mut().enumerate() {
                 if let Some(handle) = child.handle.as_mut() {
+                    // Pane Group border
+                    let divider_color = self.options.divider_color.unwrap_or(cx.theme().border);
+                    window.paint_quad(gpui::fill(handle.divider_bounds, divider_color));
+
+                    // The dividers of a locked layout can't be dragged
+                    if layout_locked {
+                        continue;
+                    }
+
                     let cursor_style = match self.axis {
                         Axis::Vertical => CursorStyle::ResizeRow,
                         Axis::Horizontal => CursorStyle::ResizeColumn,
                     };
                     window.set_cursor_style(cursor_style, &handle.hitbox);
-                    // Pane Group border
-                    window.paint_quad(gpui::fill(handle.divider_bounds, cx.theme().border));
 
                     window.on_mouse_event({
                         let dragged_handle = layout.dragged_handle.clone();
@@ -800,13 +1945,25 @@ mod element {
                         let workspace = self.workspace.clone();
                         let handle_hitbox = handle.hitbox.clone();
                         move |e: &MouseDownEvent, phase, window, cx| {
-                            if phase.bubble() && handle_hitbox.is_hovered(window) {
+                            if e.button == MouseButton::Left
+                                && phase.bubble()
+                                && handle_hitbox.is_hovered(window)
+                            {
                                 dragged_handle.replace(Some(ix));
                                 if e.click_count >= 2 {
                                     let mut borrow = flexes.lock();
-                                    *borrow = vec![1.; borrow.len()];
+                                    if e.modifiers.alt {
+                                        // Only equalize the two members next to this handle,
+                                        // their sum is unchanged so the rest keep their size.
+                                        let average = (borrow[ix] + borrow[ix + 1]) / 2.;
+                                        borrow[ix] = average;
+                                        borrow[ix + 1] = average;
+                                    } else {
+                                        *borrow = vec![1.; borrow.len()];
+                                    }
+                                    drop(borrow);
                                     workspace
-                                        .update(cx, |this, cx| this.serialize_workspace(window, cx))
+                                        .update(cx, |this, cx| this.pane_sizes_changed(window, cx))
                                         .log_err();
 
                                     window.refresh();
@@ -815,12 +1972,40 @@ mod element {
                             }
                         }
                     });
+                    window.on_mouse_event({
+                        let flexes = self.flexes.clone();
+                        let min_sizes = self.min_sizes.clone();
+                        let workspace = self.workspace.clone();
+                        let handle_hitbox = handle.hitbox.clone();
+                        let axis = self.axis;
+                        move |e: &MouseDownEvent, phase, window, cx| {
+                            if e.button == MouseButton::Middle
+                                && phase.bubble()
+                                && handle_hitbox.is_hovered(window)
+                            {
+                                Self::collapse_smaller_member(
+                                    &flexes,
+                                    &min_sizes,
+                                    ix,
+                                    axis,
+                                    bounds.size,
+                                );
+                                workspace
+                                    .update(cx, |this, cx| this.pane_sizes_changed(window, cx))
+                                    .log_err();
+                                window.refresh();
+                                cx.stop_propagation();
+                            }
+                        }
+                    });
                     window.on_mouse_event({
                         let workspace = self.workspace.clone();
                         let dragged_handle = layout.dragged_handle.clone();
                         let flexes = self.flexes.clone();
+                        let min_sizes = self.min_sizes.clone();
                         let child_bounds = child.bounds;
                         let axis = self.axis;
+                        let max_flex_ratio = self.options.max_flex_ratio;
                         move |e: &MouseMoveEvent, phase, window, cx| {
                             let dragged_handle = dragged_handle.borrow();
                             #[allow(clippy::collapsible_if)]
@@ -828,11 +2013,13 @@ mod element {
                                 if *dragged_handle == Some(ix) {
                                     Self::compute_resize(
                                         &flexes,
+                                        &min_sizes,
                                         e,
                                         ix,
                                         axis,
                                         child_bounds.origin,
                                         bounds.size,
+                                        max_flex_ratio,
                                         workspace.clone(),
                                         window,
                                         cx,
@@ -844,6 +2031,17 @@ mod element {
                 }
             }
 
+            // Above the dividers and the panes next to them
+            for child in &mut layout.children {
+                if let Some(ratio_label) = child
+                    .handle
+                    .as_mut()
+                    .and_then(|handle| handle.ratio_label.as_mut())
+                {
+                    ratio_label.paint(window, cx);
+                }
+            }
+
             window.on_mouse_event({
                 let dragged_handle = layout.dragged_handle.clone();
                 move |_: &MouseUpEvent, phase, _, _cx| {
@@ -861,7 +2059,93 @@ mod element {
         }
     }
 
-    fn flex_values_in_bounds(flexes: &[f32]) -> bool {
+    pub(super) fn flex_values_in_bounds(flexes: &[f32]) -> bool {
         (flexes.iter().copied().sum::<f32>() - flexes.len() as f32).abs() < 0.001
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    fn test_panes(count: usize, cx: &mut TestAppContext) -> Vec<Entity<Pane>> {
+        let (workspace, cx) = Workspace::test_new(cx);
+        workspace.update_in(cx, |workspace, window, cx| {
+            let mut panes = vec![workspace.active_pane().clone()];
+            workspace.add_test_item(&panes[0], "0", window, cx);
+            for ix in 1..count {
+                let pane = workspace.add_test_pane(
+                    &panes[ix - 1],
+                    SplitDirection::Right,
+                    &ix.to_string(),
+                    window,
+                    cx,
+                );
+                panes.push(pane);
+            }
+            panes
+        })
+    }
+
+    fn axis_of(panes: &[Entity<Pane>], flexes: Vec<f32>) -> PaneAxis {
+        let members = panes.iter().cloned().map(Member::Pane).collect();
+        let axis = PaneAxis::new(Axis::Horizontal, members);
+        *axis.flexes.lock() = flexes;
+        axis
+    }
+
+    fn assert_flexes(axis: &PaneAxis, expected: &[f32]) {
+        let flexes = axis.flexes.lock().clone();
+        assert_eq!(flexes.len(), expected.len());
+        for (flex, expected) in flexes.iter().zip(expected) {
+            assert!(
+                (flex - expected).abs() < 0.001,
+                "{flexes:?} != {expected:?}"
+            );
+        }
+        assert!(flex_values_in_bounds(&flexes));
+    }
+
+    #[gpui::test]
+    async fn test_expand_after_split_restores_the_collapsed_size(cx: &mut TestAppContext) {
+        let panes = test_panes(4, cx);
+        let mut axis = axis_of(&panes[..3], vec![0.5, 1., 1.5]);
+
+        assert!(axis.collapse_member(2));
+        assert_flexes(&axis, &[1., 2., 0.]);
+
+        // The collapsed member moves one over, its remembered size with it
+        axis.split(&panes[0], &panes[3], SplitDirection::Right, 0.5)
+            .unwrap();
+        assert!(axis.expand_member(3));
+        assert_flexes(&axis, &[5. / 12., 5. / 12., 5. / 3., 1.5]);
+    }
+
+    #[gpui::test]
+    async fn test_expand_after_remove_forgets_collapsed_sizes(cx: &mut TestAppContext) {
+        let panes = test_panes(3, cx);
+        let mut axis = axis_of(&panes, vec![0.5, 1., 1.5]);
+
+        assert!(axis.collapse_member(0));
+        assert_eq!(axis.remove(&panes[1]).unwrap().map(|_| ()), None);
+        assert_flexes(&axis, &[1., 1.]);
+
+        // Collapsed other than by collapse_member, e.g. in a restored layout
+        *axis.flexes.lock() = vec![0., 2.];
+        assert!(axis.expand_member(0));
+        assert_flexes(&axis, &[1., 1.]);
+    }
+
+    #[test]
+    fn test_opposite_is_an_involution() {
+        for direction in SplitDirection::all() {
+            let opposite = direction.opposite();
+            assert_ne!(opposite, direction);
+            assert_eq!(opposite.opposite(), direction);
+            assert_eq!(opposite.axis(), direction.axis());
+            assert_ne!(opposite.increasing(), direction.increasing());
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::{anyhow, Error, Result};
+use gpui::Axis;
+use serde::{Deserialize, Serialize};
+
+use super::dock::DockPosition;
+
+/// The version of the format written by [`SerializedWorkspace`].
+///
+/// Bump this when a change can't be loaded by defaulting missing fields alone, and add the
+/// step upgrading the previous version to [`SerializedWorkspace::migrate`].
+pub const SERIALIZED_WORKSPACE_VERSION: u32 = 1;
+
+/// A [`WorkspaceSnapshot`] tagged with the version of the format it was saved in, for
+/// storing layouts that need to load into later versions of this crate.
+///
+/// Older layouts are migrated as they are deserialized. Fields added to the snapshot
+/// types are marked `#[serde(default)]`, so layouts saved before them still load.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UnmigratedWorkspace")]
+pub struct SerializedWorkspace {
+    pub version: u32,
+    #[serde(flatten)]
+    pub snapshot: WorkspaceSnapshot,
+}
+
+impl SerializedWorkspace {
+    pub fn new(snapshot: WorkspaceSnapshot) -> Self {
+        Self {
+            version: SERIALIZED_WORKSPACE_VERSION,
+            snapshot,
+        }
+    }
+
+    /// Upgrade a layout saved in an older version of the format to the current one.
+    ///
+    /// Fails for layouts saved by a newer version of this crate.
+    pub fn migrate(mut self) -> Result<Self> {
+        if self.version > SERIALIZED_WORKSPACE_VERSION {
+            return Err(anyhow!(
+                "workspace layout version {} is newer than the supported version {}",
+                self.version,
+                SERIALIZED_WORKSPACE_VERSION
+            ));
+        }
+
+        // Version 0 is a bare `WorkspaceSnapshot` saved before layouts were versioned, it
+        // has the same shape as version 1.
+        self.version = SERIALIZED_WORKSPACE_VERSION;
+        Ok(self)
+    }
+}
+
+impl From<WorkspaceSnapshot> for SerializedWorkspace {
+    fn from(snapshot: WorkspaceSnapshot) -> Self {
+        Self::new(snapshot)
+    }
+}
+
+/// A [`SerializedWorkspace`] as read, before [`SerializedWorkspace::migrate`] has run.
+#[derive(Deserialize)]
+struct UnmigratedWorkspace {
+    #[serde(default)]
+    version: u32,
+    #[serde(flatten)]
+    snapshot: WorkspaceSnapshot,
+}
+
+impl TryFrom<UnmigratedWorkspace> for SerializedWorkspace {
+    type Error = Error;
+
+    fn try_from(workspace: UnmigratedWorkspace) -> Result<Self> {
+        SerializedWorkspace {
+            version: workspace.version,
+            snapshot: workspace.snapshot,
+        }
+        .migrate()
+    }
+}
+
+/// An owned, serializable snapshot of a workspace layout.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    pub center_group: SerializedPaneGroup,
+    /// The id of the active center pane.
+    #[serde(default)]
+    pub active_pane: SerializedPaneId,
+    #[serde(default)]
+    pub docks: DockStructure,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SerializedPaneGroup {
+    Group {
+        axis: Axis,
+        #[serde(default)]
+        flexes: Vec<f32>,
+        children: Vec<SerializedPaneGroup>,
+    },
+    Pane(SerializedPane),
+}
+
+impl SerializedPaneGroup {
+    /// Returns the serialized panes in tree order.
+    pub fn panes(&self) -> Vec<&SerializedPane> {
+        match self {
+            SerializedPaneGroup::Group { children, .. } => {
+                children.iter().flat_map(|child| child.panes()).collect()
+            }
+            SerializedPaneGroup::Pane(pane) => vec![pane],
+        }
+    }
+}
+
+/// Identifies a pane in serialized layouts.
+///
+/// Unlike entity ids, these are assigned by the workspace in the order panes are created,
+/// so they are stable across runs, and panes restored from a layout keep its ids.
+#[derive(
+    Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct SerializedPaneId(pub u64);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SerializedPane {
+    pub pane_id: SerializedPaneId,
+    /// The ids of the items in the pane, in tab order.
+    #[serde(default)]
+    pub items: Vec<u64>,
+    #[serde(default)]
+    pub active_item_index: usize,
+    #[serde(default)]
+    pub zoomed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DockStructure {
+    pub left: DockData,
+    pub bottom: DockData,
+    pub right: DockData,
+}
+
+impl DockStructure {
+    pub fn dock(&self, position: DockPosition) -> &DockData {
+        match position {
+            DockPosition::Left => &self.left,
+            DockPosition::Bottom => &self.bottom,
+            DockPosition::Right => &self.right,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DockData {
+    pub visible: bool,
+    /// The persistent name of the active panel.
+    pub active_panel: Option<String>,
+    pub zoom: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> WorkspaceSnapshot {
+        WorkspaceSnapshot {
+            center_group: SerializedPaneGroup::Group {
+                axis: Axis::Horizontal,
+                flexes: vec![0.5, 1.5],
+                children: vec![
+                    SerializedPaneGroup::Pane(SerializedPane {
+                        pane_id: SerializedPaneId(1),
+                        items: vec![3, 4],
+                        active_item_index: 1,
+                        zoomed: false,
+                    }),
+                    SerializedPaneGroup::Pane(SerializedPane {
+                        pane_id: SerializedPaneId(2),
+                        items: vec![5],
+                        active_item_index: 0,
+                        zoomed: false,
+                    }),
+                ],
+            },
+            active_pane: SerializedPaneId(2),
+            docks: DockStructure {
+                left: DockData {
+                    visible: true,
+                    active_panel: Some("files".to_string()),
+                    zoom: false,
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_round_trip_keeps_the_current_version() {
+        let serialized = SerializedWorkspace::new(snapshot());
+        let json = serde_json::to_value(&serialized).unwrap();
+        assert_eq!(json["version"], SERIALIZED_WORKSPACE_VERSION);
+
+        let loaded: SerializedWorkspace = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded, serialized);
+    }
+
+    #[test]
+    fn test_version_0_payload_is_migrated() {
+        // Saved before layouts were versioned: a bare snapshot without a `version` field,
+        // and without the fields that were added later.
+        let json = r#"{
+            "center_group": { "Pane": { "pane_id": 1, "items": [3] } },
+            "docks": { "left": { "visible": true, "active_panel": "files" } }
+        }"#;
+
+        let loaded: SerializedWorkspace = serde_json::from_str(json).unwrap();
+        assert_eq!(loaded.version, SERIALIZED_WORKSPACE_VERSION);
+        assert_eq!(
+            loaded.snapshot.center_group,
+            SerializedPaneGroup::Pane(SerializedPane {
+                pane_id: SerializedPaneId(1),
+                items: vec![3],
+                active_item_index: 0,
+                zoomed: false,
+            })
+        );
+        assert_eq!(loaded.snapshot.active_pane, SerializedPaneId::default());
+        assert!(loaded.snapshot.docks.left.visible);
+        assert_eq!(
+            loaded.snapshot.docks.left.active_panel.as_deref(),
+            Some("files")
+        );
+        assert!(!loaded.snapshot.docks.bottom.visible);
+
+        let reloaded: SerializedWorkspace =
+            serde_json::from_str(&serde_json::to_string(&loaded).unwrap()).unwrap();
+        assert_eq!(reloaded, loaded);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut json = serde_json::to_value(SerializedWorkspace::new(snapshot())).unwrap();
+        json["version"] = (SERIALIZED_WORKSPACE_VERSION + 1).into();
+        assert!(serde_json::from_value::<SerializedWorkspace>(json).is_err());
+
+        let serialized = SerializedWorkspace {
+            version: SERIALIZED_WORKSPACE_VERSION + 1,
+            snapshot: snapshot(),
+        };
+        assert!(serialized.migrate().is_err());
+    }
+}
@@ -1,28 +1,37 @@
 use std::{
     cmp,
-    collections::{hash_map, HashMap},
-    sync::{atomic::AtomicUsize, Arc},
+    collections::{hash_map, HashMap, HashSet, VecDeque},
+    mem,
+    rc::Rc,
     time::Duration,
 };
 
 use crate::{
-    dock::{Panel, PanelHandle},
-    pane_group,
+    dock::{DockPanel, Panel, PanelHandle},
+    item::ItemHandle,
+    pane_group::{Member, PaneAxis},
+    persistence::{
+        DockData, DockStructure, SerializedPane, SerializedPaneGroup, SerializedPaneId,
+        SerializedWorkspace, WorkspaceSnapshot,
+    },
+    util::ResultExt,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use gpui::{
-    actions, canvas, div, impl_internal_actions, prelude::FluentBuilder as _, AnyWeakView, App,
-    AppContext, Bounds, Context, Div, DragMoveEvent, Entity, EntityId, EventEmitter, FocusHandle,
-    Focusable, InteractiveElement as _, IntoElement, KeyContext, ParentElement as _, Pixels, Point,
-    Render, Styled as _, Subscription, Task, WeakEntity, Window,
+    actions, canvas, div, impl_internal_actions, prelude::FluentBuilder as _, px, size, Along,
+    AnyElement, AnyWeakView, App, AppContext, Axis, Bounds, Context, DispatchPhase, DisplayId, Div,
+    DragMoveEvent, Entity, EntityId, EventEmitter, FocusHandle, Focusable, Hsla,
+    InteractiveElement as _, IntoElement, KeyContext, MouseButton, MouseDownEvent, MouseUpEvent,
+    ParentElement as _, Pixels, Point, Render, SharedString, Size, StatefulInteractiveElement as _,
+    Styled as _, Subscription, Task, WeakEntity, Window,
 };
 use serde::Deserialize;
 use ui::{h_flex, theme::ActiveTheme};
 
 use super::{
-    dock::{Dock, DockPosition},
+    dock::{Dock, DockPosition, DockResizeSnap},
     pane::{self, Pane},
-    pane_group::{PaneGroup, SplitDirection},
+    pane_group::{PaneGroup, PaneGroupOptions, SplitDirection},
 };
 
 actions!(
@@ -30,12 +39,20 @@ actions!(
     [
         ActivateNextPane,
         ActivatePreviousPane,
+        ActivateLastFocusedPane,
+        CenterActivePane,
         CloseAllDocks,
         ToggleBottomDock,
         ToggleCenteredLayout,
         ToggleLeftDock,
         ToggleRightDock,
         ToggleZoom,
+        SwapSideDocks,
+        UndoPaneLayout,
+        SwapPaneBack,
+        RotatePaneAxis,
+        ActivateNextItemGlobal,
+        ActivatePrevItemGlobal,
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
         ReopenClosedItem,
@@ -51,14 +68,238 @@ pub struct ActivatePaneInDirection(pub SplitDirection);
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SwapPaneInDirection(pub SplitDirection);
 
+/// Move the active pane out of its nested split to the edge of the whole center layout.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct PromotePaneToRoot(pub SplitDirection);
+
+/// Split the active pane into a new pane with clones of all of its items.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct DuplicatePane(pub SplitDirection);
+
 impl_internal_actions!(
     workspace,
-    [ActivatePane, ActivatePaneInDirection, SwapPaneInDirection,]
+    [
+        ActivatePane,
+        ActivatePaneInDirection,
+        SwapPaneInDirection,
+        PromotePaneToRoot,
+        DuplicatePane,
+    ]
 );
 
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct WorkspaceId(i64);
 
+/// The order center panes are numbered in, e.g. by [`ActivatePane`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PaneOrder {
+    /// The order of the split tree, as returned by [`Workspace::panes`].
+    #[default]
+    Tree,
+    /// Top to bottom, then left to right, as last rendered.
+    Visual,
+}
+
+/// The horizontal direction the workspace is laid out in, see
+/// [`Workspace::set_layout_direction`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LayoutDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl LayoutDirection {
+    /// The side of the window a dock at `position` is shown on.
+    pub fn visual_dock_position(self, position: DockPosition) -> DockPosition {
+        match (self, position) {
+            (LayoutDirection::RightToLeft, DockPosition::Left) => DockPosition::Right,
+            (LayoutDirection::RightToLeft, DockPosition::Right) => DockPosition::Left,
+            (_, position) => position,
+        }
+    }
+
+    /// The on-screen direction `direction` moves in.
+    pub fn visual_direction(self, direction: SplitDirection) -> SplitDirection {
+        match (self, direction) {
+            (LayoutDirection::RightToLeft, SplitDirection::Left) => SplitDirection::Right,
+            (LayoutDirection::RightToLeft, SplitDirection::Right) => SplitDirection::Left,
+            (_, direction) => direction,
+        }
+    }
+}
+
+/// Options controlling how a [`Workspace`] is set up by [`Workspace::new_with_options`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorkspaceOptions {
+    /// Whether to focus the center pane once the workspace is created.
+    ///
+    /// Embedders restoring a layout can disable this and focus the restored pane themselves.
+    pub focus_on_create: bool,
+    /// Whether to focus the workspace again whenever the window loses focus.
+    ///
+    /// Apps with focusable surfaces outside the workspace, like menus or palettes, can
+    /// disable this so focus isn't taken back from them. See [`Workspace::set_trap_focus`].
+    pub trap_focus: bool,
+}
+
+impl Default for WorkspaceOptions {
+    fn default() -> Self {
+        Self {
+            focus_on_create: true,
+            trap_focus: true,
+        }
+    }
+}
+
+/// How long the mouse has to rest on a pane before it's focused, see
+/// [`Workspace::set_focus_follows_mouse`].
+const FOCUS_FOLLOWS_MOUSE_DELAY: Duration = Duration::from_millis(150);
+
+/// How many pane layouts [`Workspace::undo_pane_layout`] can step back through.
+const MAX_PANE_LAYOUT_HISTORY: usize = 20;
+
+/// Where focus is when moving it with [`Workspace::activate_pane_in_direction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavOrigin {
+    /// A center pane, and whether it has a neighboring pane in the direction moved in.
+    Center {
+        has_pane_in_direction: bool,
+    },
+    Dock(DockPosition),
+}
+
+/// Which docks are open, as seen by [`resolve_navigation_target`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DocksOpen {
+    pub left: bool,
+    pub right: bool,
+    pub bottom: bool,
+}
+
+impl DocksOpen {
+    pub fn is_open(&self, position: DockPosition) -> bool {
+        match position {
+            DockPosition::Left => self.left,
+            DockPosition::Right => self.right,
+            DockPosition::Bottom => self.bottom,
+        }
+    }
+}
+
+/// Where [`Workspace::activate_pane_in_direction`] moves focus to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavTarget {
+    /// The neighboring center pane in the direction moved in.
+    PaneInDirection,
+    /// The last active center pane.
+    LastActivePane,
+    Dock(DockPosition),
+    /// Focus stays where it is.
+    None,
+}
+
+/// Resolves where moving focus in `direction` from `origin` goes, without needing a window.
+///
+/// The navigation graph between the center and the docks:
+///
+/// - Center: move to the neighboring pane in `direction`, or else to the dock on that
+///   side (left, right or bottom). There is nothing above the center.
+/// - Left dock: right and up go to the last active center pane, falling back to the
+///   bottom dock then the right dock when moving right; down goes to the bottom dock,
+///   falling back to the last active center pane.
+/// - Right dock: mirrors the left dock.
+/// - Bottom dock: up goes to the last active center pane, left and right go to the
+///   dock on that side.
+///
+/// Moving towards the edge of the window (left from the left dock, right from the
+/// right dock, down from the bottom dock) does nothing.
+pub fn resolve_navigation_target(
+    origin: NavOrigin,
+    direction: SplitDirection,
+    docks_open: DocksOpen,
+    has_last_active_pane: bool,
+) -> NavTarget {
+    let try_dock = |position: DockPosition| {
+        if docks_open.is_open(position) {
+            NavTarget::Dock(position)
+        } else {
+            NavTarget::None
+        }
+    };
+    let last_active_pane_or = |fallback: NavTarget| {
+        if has_last_active_pane {
+            NavTarget::LastActivePane
+        } else {
+            fallback
+        }
+    };
+    let or_else = |target: NavTarget, fallback: NavTarget| {
+        if target == NavTarget::None {
+            fallback
+        } else {
+            target
+        }
+    };
+
+    match (origin, direction) {
+        // We're in the center, so we first try to go to a different pane,
+        // otherwise try to go to a dock.
+        (
+            NavOrigin::Center {
+                has_pane_in_direction: true,
+            },
+            _,
+        ) => NavTarget::PaneInDirection,
+        (NavOrigin::Center { .. }, direction) => match direction {
+            SplitDirection::Up => NavTarget::None,
+            SplitDirection::Down => try_dock(DockPosition::Bottom),
+            SplitDirection::Left => try_dock(DockPosition::Left),
+            SplitDirection::Right => try_dock(DockPosition::Right),
+        },
+
+        (NavOrigin::Dock(DockPosition::Left), SplitDirection::Right) => {
+            last_active_pane_or(or_else(
+                try_dock(DockPosition::Bottom),
+                try_dock(DockPosition::Right),
+            ))
+        }
+        (NavOrigin::Dock(DockPosition::Right), SplitDirection::Left) => last_active_pane_or(
+            or_else(try_dock(DockPosition::Bottom), try_dock(DockPosition::Left)),
+        ),
+
+        (NavOrigin::Dock(DockPosition::Left), SplitDirection::Up)
+        | (NavOrigin::Dock(DockPosition::Right), SplitDirection::Up)
+        | (NavOrigin::Dock(DockPosition::Bottom), SplitDirection::Up) => {
+            last_active_pane_or(NavTarget::None)
+        }
+
+        (NavOrigin::Dock(DockPosition::Left), SplitDirection::Down)
+        | (NavOrigin::Dock(DockPosition::Right), SplitDirection::Down) => or_else(
+            try_dock(DockPosition::Bottom),
+            last_active_pane_or(NavTarget::None),
+        ),
+
+        (NavOrigin::Dock(DockPosition::Bottom), SplitDirection::Left) => {
+            try_dock(DockPosition::Left)
+        }
+        (NavOrigin::Dock(DockPosition::Bottom), SplitDirection::Right) => {
+            try_dock(DockPosition::Right)
+        }
+
+        (NavOrigin::Dock(DockPosition::Left), SplitDirection::Left)
+        | (NavOrigin::Dock(DockPosition::Right), SplitDirection::Right)
+        | (NavOrigin::Dock(DockPosition::Bottom), SplitDirection::Down) => NavTarget::None,
+    }
+}
+
+/// A dock closed by [`Workspace::hide_all_docks`], as it was before.
+struct HiddenDock {
+    position: DockPosition,
+    active_panel_index: usize,
+    size: Option<Pixels>,
+}
+
 enum ActivateInDirectionTarget {
     Pane(Entity<Pane>),
     Dock(Entity<Dock>),
@@ -79,20 +320,89 @@ pub struct Workspace {
     pub(crate) zoomed: Option<AnyWeakView>,
     pub(crate) zoomed_position: Option<DockPosition>,
     database_id: Option<WorkspaceId>,
+    /// Only valid once the workspace has been painted, empty before that, see
+    /// [`Workspace::is_laid_out`].
     bounds: Bounds<Pixels>,
+    pane_group_options: PaneGroupOptions,
+    dock_resize_snap: Option<DockResizeSnap>,
+    resizing_dock: Option<DockPosition>,
+    dock_drag_threshold: Pixels,
+    /// Where the left mouse button was last pressed, for [`Workspace::set_dock_drag_threshold`].
+    mouse_press_position: Option<Point<Pixels>>,
+    bottom_dock_full_width: bool,
+    layout_direction: LayoutDirection,
+    focus_follows_mouse: bool,
+    focus_on_hover_task: Option<(WeakEntity<Pane>, Task<()>)>,
+    dock_gutter_size: Option<Pixels>,
+    split_empty_panes: bool,
+    layout_locked: bool,
+    trap_focus: bool,
+    close_window_when_empty: bool,
+    reported_empty: bool,
+    last_swap_direction: Option<SplitDirection>,
+    panes_by_recent_use: Vec<WeakEntity<Pane>>,
+    pane_index_order: PaneOrder,
     workspace_actions: Vec<Box<dyn Fn(Div, &mut Window, &mut Context<Self>) -> Div>>,
+    can_remove_pane: Vec<Box<dyn Fn(&Entity<Pane>, &App) -> bool>>,
+    empty_pane_view: Option<Rc<dyn Fn(&mut Window, &mut App) -> AnyElement>>,
+    pane_factory: Option<Box<dyn Fn(WeakEntity<Self>, &mut Window, &mut Context<Pane>) -> Pane>>,
     bounds_save_task_queued: Option<Task<()>>,
+    bounds_save_debounce: Option<Duration>,
+    next_serialized_pane_id: u64,
+    last_display_id: Option<DisplayId>,
+    serialization_sink: Option<Box<dyn Fn(SerializedWorkspace, &mut App)>>,
+    layouts: HashMap<String, WorkspaceSnapshot>,
+    pane_layout_history: VecDeque<(PaneGroup, Entity<Pane>)>,
+    hidden_docks: Vec<HiddenDock>,
+    layout_batch_depth: usize,
+    layout_batch_notify: bool,
+    layout_batch_serialize: bool,
+    /// Bulk closes of items still running, see [`Workspace::close_all_items_and_panes`].
+    /// Meanwhile panes left empty are queued in `pending_pane_removals` and removed, and
+    /// the workspace serialized, once all of them are done.
+    bulk_close_depth: usize,
+    pending_pane_removals: Vec<WeakEntity<Pane>>,
+    _schedule_serialize: Option<Task<()>>,
+    _schedule_pane_sizes_changed: Option<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
 pub enum Event {
     PaneAdded(Entity<Pane>),
     PaneRemoved,
+    /// `new` was added to the center group by splitting `source` in `direction`.
+    PaneSplit {
+        source: Entity<Pane>,
+        new: Entity<Pane>,
+        direction: SplitDirection,
+    },
     ItemAdded,
     ItemRemoved,
+    /// An item was dragged or moved with [`Workspace::move_item`] from one pane to another.
+    ItemMoved {
+        item_id: EntityId,
+        from: WeakEntity<Pane>,
+        to: WeakEntity<Pane>,
+    },
+    /// An item was moved within its pane by [`Workspace::move_item`], now at `index`.
+    ItemReordered {
+        item_id: EntityId,
+        pane: WeakEntity<Pane>,
+        index: usize,
+    },
     ActiveItemChanged,
     WorkspaceCreated(WeakEntity<Workspace>),
     ZoomChanged,
+    /// The relative sizes of the center panes changed, emitted at most every 100ms.
+    PaneSizesChanged,
+    PaneLabelChanged(Entity<Pane>),
+    DockStateChanged {
+        position: DockPosition,
+        open: bool,
+    },
+    DockPanelsReordered(DockPosition),
+    /// The last item of the workspace was closed.
+    WorkspaceEmpty,
 }
 
 impl EventEmitter<Event> for Workspace {}
@@ -111,6 +421,17 @@ impl Render for Workspace {
         let mut context = KeyContext::new_with_defaults();
         context.add("Workspace");
 
+        // The zoomed view was released without zooming out, don't render an empty overlay.
+        if self
+            .zoomed
+            .as_ref()
+            .is_some_and(|zoomed| zoomed.upgrade().is_none())
+        {
+            self.zoomed = None;
+            self.zoomed_position = None;
+            cx.emit(Event::ZoomChanged);
+        }
+
         // let render_padding = |size| {
         //     (size > 0.0).then(|| {
         //         div()
@@ -145,9 +466,19 @@ impl Render for Workspace {
                     .overflow_hidden()
                     .child({
                         let this = cx.entity().clone();
+                        let weak_this = self.weak_self.clone();
                         canvas(
                             move |bounds, _, cx| this.update(cx, |this, _cx| this.bounds = bounds),
-                            |_, _, _, _| {},
+                            move |_, _, window, _| {
+                                // A dock drag can end outside the window or without a drop
+                                window.on_mouse_event(move |_: &MouseUpEvent, phase, _, cx| {
+                                    if phase == DispatchPhase::Capture {
+                                        weak_this
+                                            .update(cx, |this, cx| this.end_dock_drag(cx))
+                                            .ok();
+                                    }
+                                });
+                            },
                         )
                         .absolute()
                         .size_full()
@@ -155,34 +486,66 @@ impl Render for Workspace {
                     .when(self.zoomed.is_none(), |this| {
                         this.on_drag_move(cx.listener(
                             |workspace, e: &DragMoveEvent<DraggedDock>, window, cx| {
-                                match e.drag(cx).0 {
+                                // Sizes are measured from the workspace edges
+                                if !workspace.is_laid_out() {
+                                    return;
+                                }
+                                let position = e.drag(cx).0;
+                                if workspace.resizing_dock != Some(position) {
+                                    let moved = workspace.mouse_press_position.map_or(
+                                        workspace.dock_drag_threshold,
+                                        |press| {
+                                            (e.event.position.along(position.axis())
+                                                - press.along(position.axis()))
+                                            .abs()
+                                        },
+                                    );
+                                    if moved < workspace.dock_drag_threshold {
+                                        return;
+                                    }
+                                }
+                                let visual_position =
+                                    workspace.layout_direction.visual_dock_position(position);
+                                let size = match visual_position {
                                     DockPosition::Left => {
-                                        let size = workspace.bounds.left() + e.event.position.x;
-                                        workspace.left_dock.update(cx, |left_dock, cx| {
-                                            left_dock.resize_active_panel(Some(size), window, cx);
-                                        });
+                                        workspace.bounds.left() + e.event.position.x
                                     }
                                     DockPosition::Right => {
-                                        let size = workspace.bounds.right() - e.event.position.x;
-                                        workspace.right_dock.update(cx, |right_dock, cx| {
-                                            right_dock.resize_active_panel(Some(size), window, cx);
-                                        });
+                                        workspace.bounds.right() - e.event.position.x
                                     }
                                     DockPosition::Bottom => {
-                                        let size = workspace.bounds.bottom() - e.event.position.y;
-                                        workspace.bottom_dock.update(cx, |bottom_dock, cx| {
-                                            bottom_dock.resize_active_panel(Some(size), window, cx);
-                                        });
+                                        workspace.bounds.bottom() - e.event.position.y
                                     }
+                                };
+                                let size = workspace.clamp_dock_size(position, size);
+                                if workspace.resizing_dock != Some(position) {
+                                    workspace.resizing_dock = Some(position);
+                                    cx.notify();
                                 }
+                                workspace.resize_dock(position, size, window, cx);
                             },
                         ))
                     })
+                    .capture_any_mouse_down(cx.listener(|workspace, e: &MouseDownEvent, _, _| {
+                        if e.button == MouseButton::Left {
+                            workspace.mouse_press_position = Some(e.position);
+                        }
+                    }))
+                    .on_drop(
+                        cx.listener(|workspace, _: &DraggedDock, _, cx| {
+                            workspace.end_dock_drag(cx)
+                        }),
+                    )
                     .child(
                         div()
                             .flex()
                             .flex_row()
-                            .h_full()
+                            .when(
+                                self.layout_direction == LayoutDirection::RightToLeft,
+                                |this| this.flex_row_reverse(),
+                            )
+                            .when(self.bottom_dock_full_width, |this| this.flex_1().min_h_0())
+                            .when(!self.bottom_dock_full_width, |this| this.h_full())
                             // Left Dock
                             .children(self.zoomed_position.ne(&Some(DockPosition::Left)).then(
                                 || {
@@ -193,6 +556,7 @@ impl Render for Workspace {
                                         .child(self.left_dock.clone())
                                 },
                             ))
+                            .children(self.render_dock_gutter(DockPosition::Left, cx))
                             // Panes
                             .child(
                                 div()
@@ -203,15 +567,22 @@ impl Render for Workspace {
                                     .child(h_flex().flex_1().child(self.center.render(
                                         &self.active_pane,
                                         self.zoomed.as_ref(),
+                                        self.pane_group_options,
                                         window,
                                         cx,
                                     )))
+                                    .when(!self.bottom_dock_full_width, |this| {
+                                        this.children(
+                                            self.render_dock_gutter(DockPosition::Bottom, cx),
+                                        )
+                                    })
                                     .children(
-                                        self.zoomed_position
-                                            .ne(&Some(DockPosition::Bottom))
-                                            .then(|| self.bottom_dock.clone()),
+                                        (!self.bottom_dock_full_width
+                                            && self.zoomed_position != Some(DockPosition::Bottom))
+                                        .then(|| self.bottom_dock.clone()),
                                     ),
                             )
+                            .children(self.render_dock_gutter(DockPosition::Right, cx))
                             // Right Dock
                             .children(self.zoomed_position.ne(&Some(DockPosition::Right)).then(
                                 || {
@@ -223,6 +594,15 @@ impl Render for Workspace {
                                 },
                             )),
                     )
+                    // Full width bottom dock
+                    .when(self.bottom_dock_full_width, |this| {
+                        this.children(self.render_dock_gutter(DockPosition::Bottom, cx))
+                    })
+                    .children(
+                        (self.bottom_dock_full_width
+                            && self.zoomed_position != Some(DockPosition::Bottom))
+                        .then(|| self.bottom_dock.clone()),
+                    )
                     .children(self.zoomed.as_ref().and_then(|view| {
                         let zoomed_view = view.upgrade()?;
                         let div = div()
@@ -235,7 +615,10 @@ impl Render for Workspace {
                             .inset_0()
                             .shadow_lg();
 
-                        Some(match self.zoomed_position {
+                        let zoomed_position = self
+                            .zoomed_position
+                            .map(|position| self.layout_direction.visual_dock_position(position));
+                        Some(match zoomed_position {
                             Some(DockPosition::Left) => div.right_2().border_r_1(),
                             Some(DockPosition::Right) => div.left_2().border_l_1(),
                             Some(DockPosition::Bottom) => div.top_2().border_t_1(),
@@ -251,20 +634,37 @@ impl Workspace {
         workspace_id: Option<WorkspaceId>,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new_with_options(workspace_id, WorkspaceOptions::default(), window, cx)
+    }
+
+    pub fn new_with_options(
+        workspace_id: Option<WorkspaceId>,
+        options: WorkspaceOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Self {
         cx.on_focus_lost(window, |this, window, cx| {
+            if !this.trap_focus {
+                return;
+            }
             let focus_handle = this.focus_handle(cx);
             window.focus(&focus_handle);
         })
         .detach();
 
         let weak_handle = cx.entity().downgrade();
-        let _pane_history_timestamp = Arc::new(AtomicUsize::new(0));
 
-        let center_pane = cx.new(|cx| Pane::new(weak_handle.clone(), None, window, cx));
+        let center_pane = cx.new(|cx| {
+            let mut pane = Pane::new(weak_handle.clone(), None, window, cx);
+            pane.set_serialized_id(SerializedPaneId(1));
+            pane
+        });
         cx.subscribe_in(&center_pane, window, Self::handle_pane_event)
             .detach();
-        cx.focus_view(&center_pane, window);
+        if options.focus_on_create {
+            cx.focus_view(&center_pane, window);
+        }
         cx.emit(Event::PaneAdded(center_pane.clone()));
         // let window_handle = cx.window_handle().downcast::<Workspace>().unwrap();
 
@@ -279,14 +679,24 @@ impl Workspace {
         let subscriptions = vec![
             cx.observe_window_activation(window, Self::on_window_activation_changed),
             cx.observe_window_bounds(window, move |this, window, cx| {
-                if this.bounds_save_task_queued.is_some() {
+                // Moving to another display saves right away, so the saved bounds never
+                // carry the previous display's identity.
+                let display_id = window.display(cx).map(|display| display.id());
+                let display_changed = display_id != this.last_display_id;
+                if this.bounds_save_task_queued.is_some() && !display_changed {
                     return;
                 }
+                this.last_display_id = display_id;
+                let debounce = if display_changed {
+                    None
+                } else {
+                    this.bounds_save_debounce
+                };
                 this.bounds_save_task_queued =
                     Some(cx.spawn_in(window, |this, mut cx| async move {
-                        cx.background_executor()
-                            .timer(Duration::from_millis(100))
-                            .await;
+                        if let Some(debounce) = debounce {
+                            cx.background_executor().timer(debounce).await;
+                        }
                         this.update_in(&mut cx, |this, window, cx| {
                             if let Some(display) = window.display(cx) {
                                 if let Ok(_display_uuid) = display.uuid() {
@@ -342,9 +752,44 @@ impl Workspace {
             right_dock,
             database_id: workspace_id,
             workspace_actions: Default::default(),
+            can_remove_pane: Default::default(),
+            pane_factory: None,
+            empty_pane_view: None,
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
+            pane_group_options: PaneGroupOptions::default(),
+            dock_resize_snap: None,
+            resizing_dock: None,
+            dock_drag_threshold: px(0.),
+            mouse_press_position: None,
+            bottom_dock_full_width: false,
+            layout_direction: LayoutDirection::default(),
+            focus_follows_mouse: false,
+            focus_on_hover_task: None,
+            dock_gutter_size: None,
+            split_empty_panes: false,
+            layout_locked: false,
+            trap_focus: options.trap_focus,
+            close_window_when_empty: false,
+            reported_empty: false,
+            last_swap_direction: None,
+            panes_by_recent_use: vec![center_pane.downgrade()],
+            pane_index_order: PaneOrder::default(),
             bounds_save_task_queued: None,
+            bounds_save_debounce: Some(Duration::from_millis(100)),
+            next_serialized_pane_id: 2,
+            last_display_id: window.display(cx).map(|display| display.id()),
+            serialization_sink: None,
+            layouts: HashMap::default(),
+            pane_layout_history: VecDeque::new(),
+            hidden_docks: Vec::new(),
+            layout_batch_depth: 0,
+            layout_batch_notify: false,
+            layout_batch_serialize: false,
+            bulk_close_depth: 0,
+            pending_pane_removals: Vec::new(),
+            _schedule_serialize: None,
+            _schedule_pane_sizes_changed: None,
             _subscriptions: subscriptions,
         }
     }
@@ -404,6 +849,11 @@ impl Workspace {
             .on_action(cx.listener(|workspace, _: &ActivateNextPane, window, cx| {
                 workspace.activate_next_pane(window, cx)
             }))
+            .on_action(
+                cx.listener(|workspace, _: &ActivateLastFocusedPane, window, cx| {
+                    workspace.activate_last_focused_pane(window, cx)
+                }),
+            )
             .on_action(
                 cx.listener(|workspace, action: &ActivatePaneInDirection, window, cx| {
                     workspace.activate_pane_in_direction(action.0, window, cx)
@@ -414,6 +864,40 @@ impl Workspace {
                     workspace.swap_pane_in_direction(action.0, window, cx)
                 }),
             )
+            .on_action(
+                cx.listener(|workspace, action: &PromotePaneToRoot, window, cx| {
+                    let pane = workspace.active_pane.clone();
+                    workspace.promote_pane_to_root(&pane, action.0, window, cx);
+                }),
+            )
+            .on_action(
+                cx.listener(|workspace, action: &DuplicatePane, window, cx| {
+                    let pane = workspace.active_pane.clone();
+                    workspace.duplicate_pane(pane, action.0, window, cx);
+                }),
+            )
+            .on_action(cx.listener(|workspace, _: &SwapPaneBack, window, cx| {
+                workspace.swap_pane_back(window, cx)
+            }))
+            .on_action(cx.listener(|workspace, _: &RotatePaneAxis, window, cx| {
+                workspace.rotate_pane_axis(window, cx)
+            }))
+            .on_action(cx.listener(|workspace, _: &CenterActivePane, window, cx| {
+                workspace.center_active_pane(window, cx);
+            }))
+            .on_action(
+                cx.listener(|workspace, _: &ActivateNextItemGlobal, window, cx| {
+                    workspace.activate_item_across_panes(true, window, cx)
+                }),
+            )
+            .on_action(
+                cx.listener(|workspace, _: &ActivatePrevItemGlobal, window, cx| {
+                    workspace.activate_item_across_panes(false, window, cx)
+                }),
+            )
+            .on_action(cx.listener(|workspace, _: &SwapSideDocks, window, cx| {
+                workspace.swap_side_docks(window, cx);
+            }))
             .on_action(cx.listener(|this, _: &ToggleLeftDock, window, cx| {
                 this.toggle_dock(DockPosition::Left, window, cx);
             }))
@@ -432,6 +916,12 @@ impl Workspace {
                     workspace.close_all_docks(window, cx);
                 }),
             )
+            .on_action(cx.listener(|workspace, _: &ToggleZoom, window, cx| {
+                workspace.toggle_zoom(window, cx);
+            }))
+            .on_action(cx.listener(|workspace, _: &UndoPaneLayout, window, cx| {
+                workspace.undo_pane_layout(window, cx);
+            }))
             .on_action(cx.listener(Workspace::activate_pane_at_index))
             .on_action(cx.listener(
                 |_workspace: &mut Workspace, _: &ReopenClosedItem, _window, _cx| {
@@ -441,17 +931,29 @@ impl Workspace {
     }
 
     pub fn add_panel<T: Panel>(&mut self, panel: Entity<T>, window: &mut Window, cx: &mut App) {
-        let dock = match panel.position(window, cx) {
-            DockPosition::Left => &self.left_dock,
-            DockPosition::Bottom => &self.bottom_dock,
-            DockPosition::Right => &self.right_dock,
-        };
+        let dock = self.dock_at(panel.position(window, cx));
 
         dock.update(cx, |dock, cx| {
             dock.add_panel(panel, self.weak_self.clone(), window, cx)
         });
     }
 
+    /// Add several panels at once, e.g. a fixed set of panels right after creating the
+    /// workspace so its docks are never rendered empty. The workspace is notified and
+    /// serialized once after all of them are added.
+    pub fn add_panels(
+        &mut self,
+        panels: impl IntoIterator<Item = DockPanel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for panel in panels {
+            panel.add_to(self, window, cx);
+        }
+        cx.notify();
+        self.serialize_workspace(window, cx);
+    }
+
     pub fn close_inactive_items_and_panes(
         &mut self,
         _action: &CloseInactiveTabsAndPanes,
@@ -474,31 +976,48 @@ impl Workspace {
         }
     }
 
+    /// Close the items of every pane, except for pinned items.
+    ///
+    /// When `retain_active_pane` is set the active item of the active pane is kept open too.
     fn close_all_internal(
         &mut self,
         retain_active_pane: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Task<Result<()>>> {
-        let current_pane = self.active_pane();
+        let current_pane = self.active_pane().clone();
 
         let mut tasks = Vec::new();
 
+        let close_items = |pane: &mut Pane,
+                           should_close: &dyn Fn(EntityId) -> bool,
+                           window: &mut Window,
+                           cx: &mut Context<Pane>| {
+            if pane.items_len() == 0 {
+                None
+            } else {
+                Some(pane.close_unpinned_items(window, cx, should_close))
+            }
+        };
+
         if retain_active_pane {
             if let Some(current_pane_close) = current_pane.update(cx, |pane, cx| {
-                pane.close_inactive_items(&pane::CloseInactiveItems, window, cx)
+                let active_item_id = pane.active_item().map(|item| item.item_id());
+                close_items(pane, &|item_id| Some(item_id) != active_item_id, window, cx)
             }) {
                 tasks.push(current_pane_close);
             };
         }
 
-        for pane in self.panes() {
+        // Panes emptied by the closes are only removed once all of them are done, but don't
+        // rely on the list staying put while items are closed.
+        for pane in self.panes.clone() {
             if retain_active_pane && pane.entity_id() == current_pane.entity_id() {
                 continue;
             }
 
             if let Some(close_pane_items) = pane.update(cx, |pane: &mut Pane, cx| {
-                pane.close_all_items(&pane::CloseAllItems, window, cx)
+                close_items(pane, &|_| true, window, cx)
             }) {
                 tasks.push(close_pane_items)
             }
@@ -507,15 +1026,95 @@ impl Workspace {
         if tasks.is_empty() {
             None
         } else {
-            Some(cx.spawn(|_, _| async move {
+            self.bulk_close_depth += 1;
+            Some(cx.spawn_in(window, |this, mut cx| async move {
+                let mut result = Ok(());
                 for task in tasks {
-                    task.await?
+                    if let Err(error) = task.await {
+                        result = Err(error);
+                        break;
+                    }
                 }
-                Ok(())
+                this.update_in(&mut cx, |this, window, cx| {
+                    this.finish_bulk_close(window, cx)
+                })?;
+                result
             }))
         }
     }
 
+    /// Remove the panes queued while bulk closing and serialize once, after the last
+    /// bulk close is done.
+    fn finish_bulk_close(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.bulk_close_depth = self.bulk_close_depth.saturating_sub(1);
+        if self.bulk_close_depth > 0 {
+            return;
+        }
+
+        for pane in mem::take(&mut self.pending_pane_removals) {
+            // The layout can have changed while the items were closing, and items can have
+            // been moved into the pane after it was emptied.
+            if let Some(pane) = pane
+                .upgrade()
+                .filter(|pane| self.panes.contains(pane) && pane.read(cx).items_len() == 0)
+            {
+                self.remove_pane(&pane, window, cx);
+            }
+        }
+        self.report_if_empty(window, cx);
+        if self.layout_batch_depth == 0 && mem::take(&mut self.layout_batch_serialize) {
+            self.serialize_workspace(window, cx);
+        }
+    }
+
+    /// Close every item, in any pane, for which `predicate` returns `true`.
+    ///
+    /// Panes left empty are removed, unless they are the last pane.
+    pub fn close_items_matching(
+        &mut self,
+        predicate: impl Fn(&dyn ItemHandle) -> bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let mut tasks = Vec::new();
+        for pane in self.panes() {
+            let item_ids = pane
+                .read(cx)
+                .items()
+                .filter(|item| predicate(item.as_ref()))
+                .map(|item| item.item_id())
+                .collect::<HashSet<_>>();
+            if item_ids.is_empty() {
+                continue;
+            }
+
+            tasks.push(pane.update(cx, |pane, cx| {
+                pane.close_items(window, cx, move |item_id| item_ids.contains(&item_id))
+            }));
+        }
+
+        cx.spawn(|_, _| async move {
+            for task in tasks {
+                task.await?
+            }
+            Ok(())
+        })
+    }
+
+    /// Update every center pane with `f`.
+    ///
+    /// The panes are collected up front, so `f` may close items or panes as it goes.
+    pub fn for_each_pane(
+        &mut self,
+        mut f: impl FnMut(&mut Pane, &mut Window, &mut Context<Pane>),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for pane in self.panes.clone() {
+            pane.update(cx, |pane, cx| f(pane, window, cx));
+        }
+    }
+
     pub fn weak_handle(&self) -> WeakEntity<Self> {
         self.weak_self.clone()
     }
@@ -532,536 +1131,2964 @@ impl Workspace {
         &self.right_dock
     }
 
-    pub fn database_id(&self) -> Option<WorkspaceId> {
-        self.database_id
-    }
-
-    fn add_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Pane> {
-        let pane = cx.new(|cx| Pane::new(self.weak_handle(), None, window, cx));
-        cx.subscribe_in(&pane, window, Self::handle_pane_event)
-            .detach();
-        self.panes.push(pane.clone());
-        cx.focus_view(&pane, window);
-        cx.emit(Event::PaneAdded(pane.clone()));
-        pane
-    }
-
-    pub fn split_pane(
+    /// Move a panel of the dock at `position` from `from_ix` to `to_ix`.
+    pub fn reorder_panel(
         &mut self,
-        pane_to_split: Entity<Pane>,
-        split_direction: SplitDirection,
-        window: &mut Window,
+        position: DockPosition,
+        from_ix: usize,
+        to_ix: usize,
         cx: &mut Context<Self>,
-    ) -> Entity<Pane> {
-        let new_pane = self.add_pane(window, cx);
-        self.center
-            .split(&pane_to_split, &new_pane, split_direction)
-            .unwrap();
-        cx.notify();
-        new_pane
+    ) {
+        let reordered = self
+            .dock_at(position)
+            .update(cx, |dock, cx| dock.reorder_panel(from_ix, to_ix, cx));
+        if reordered && from_ix != to_ix {
+            cx.emit(Event::DockPanelsReordered(position));
+        }
     }
 
-    pub fn split_and_clone(
+    /// Set the size of the active panel of the dock at `position`, e.g. when restoring a
+    /// workspace. The size is clamped like when resizing the dock by dragging, but not snapped.
+    pub fn set_dock_size(
         &mut self,
-        pane: Entity<Pane>,
-        direction: SplitDirection,
+        position: DockPosition,
+        size: Pixels,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Entity<Pane>> {
-        let item = pane.read(cx).active_item()?;
-        let maybe_pane_handle =
-            if let Some(clone) = item.clone_on_split(self.database_id(), window, cx) {
-                let new_pane = self.add_pane(window, cx);
-                new_pane.update(cx, |pane, cx| {
-                    pane.add_item(clone, true, true, None, window, cx)
-                });
-                self.center.split(&pane, &new_pane, direction).unwrap();
-                Some(new_pane)
-            } else {
-                None
-            };
+    ) {
+        if self.layout_locked {
+            return;
+        }
+        let size = self.clamp_dock_size(position, size);
+        self.dock_at(position).update(cx, |dock, cx| {
+            dock.resize_active_panel(Some(size), window, cx)
+        });
         cx.notify();
-        maybe_pane_handle
+        self.serialize_workspace(window, cx);
     }
 
-    pub fn split_pane_with_item(
-        &mut self,
-        pane_to_split: WeakEntity<Pane>,
-        split_direction: SplitDirection,
-        from: WeakEntity<Pane>,
-        item_id_to_move: EntityId,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let Some(pane_to_split) = pane_to_split.upgrade() else {
-            return;
+    /// Keep a dock size within the workspace, e.g. when dragging past its opposite edge.
+    fn clamp_dock_size(&self, position: DockPosition, size: Pixels) -> Pixels {
+        let max_size = match position.axis() {
+            Axis::Horizontal => self.bounds.size.width,
+            Axis::Vertical => self.bounds.size.height,
         };
-        let Some(from) = from.upgrade() else {
-            return;
+        // The bounds are unknown until the workspace is first laid out.
+        let size = if max_size > px(0.) {
+            size.min(max_size)
+        } else {
+            size
         };
+        size.max(px(0.))
+    }
 
-        let new_pane = self.add_pane(window, cx);
-        self.move_item(
-            from.clone(),
-            new_pane.clone(),
-            item_id_to_move,
-            0,
-            window,
-            cx,
-        );
-        self.center
-            .split(&pane_to_split, &new_pane, split_direction)
-            .unwrap();
-        cx.notify();
+    pub fn dock_is_open(&self, position: DockPosition, cx: &App) -> bool {
+        self.dock_at(position).read(cx).is_open()
     }
 
-    pub fn move_item(
-        &mut self,
-        source: Entity<Pane>,
-        destination: Entity<Pane>,
-        item_id_to_move: EntityId,
-        destination_index: usize,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let Some((item_ix, item_handle)) = source
+    /// The [`Panel::persistent_name`] of the active panel of the dock at `position`, whether
+    /// or not the dock is open.
+    pub fn dock_active_panel_name(&self, position: DockPosition, cx: &App) -> Option<&'static str> {
+        self.dock_at(position)
             .read(cx)
-            .items()
-            .enumerate()
-            .find(|(_, item_handle)| item_handle.item_id() == item_id_to_move)
-        else {
-            // Tab was closed during drag
-            return;
-        };
+            .active_panel()
+            .map(|panel| panel.persistent_name())
+    }
 
-        let item_handle = item_handle.clone();
+    /// The id of the active panel of the dock at `position`, whether or not the dock is open.
+    pub fn dock_active_panel_id(&self, position: DockPosition, cx: &App) -> Option<EntityId> {
+        self.dock_at(position)
+            .read(cx)
+            .active_panel()
+            .map(|panel| panel.id())
+    }
 
-        if source != destination {
-            // Close item from previous pane
-            source.update(cx, |source, cx| {
-                source.remove_item(item_ix, false, window, cx);
-            });
+    pub fn dock_at(&self, position: DockPosition) -> &Entity<Dock> {
+        match position {
+            DockPosition::Left => &self.left_dock,
+            DockPosition::Bottom => &self.bottom_dock,
+            DockPosition::Right => &self.right_dock,
         }
+    }
 
-        // This automatically removes duplicate items in the pane
-        destination.update(cx, |destination, cx| {
-            destination.add_item(item_handle, true, true, Some(destination_index), window, cx);
-            destination.focus(window)
-        });
+    /// All docks with their position, in left, bottom, right order.
+    pub fn docks(&self) -> [(DockPosition, &Entity<Dock>); 3] {
+        [
+            (DockPosition::Left, &self.left_dock),
+            (DockPosition::Bottom, &self.bottom_dock),
+            (DockPosition::Right, &self.right_dock),
+        ]
     }
 
-    fn remove_pane(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
-        if self.center.remove(pane).unwrap() {
-            self.force_remove_pane(pane, window, cx);
+    /// The smallest size the workspace fits in without squeezing the open docks or a center
+    /// pane, e.g. to set as the minimum window size.
+    ///
+    /// It changes as docks open and close, see [`Event::DockStateChanged`], and as the center
+    /// panes are split and removed.
+    pub fn minimum_size(&self, window: &Window, cx: &App) -> Size<Pixels> {
+        let center = self.center.minimum_size(cx);
+        let dock_size = |position| {
+            let dock = self.dock_at(position).read(cx);
+            let gutter_size = match self.dock_gutter_size {
+                Some(gutter_size) if dock.is_open() => gutter_size,
+                _ => px(0.),
+            };
+            dock.visible_size(window, cx) + gutter_size
+        };
+        size(
+            dock_size(DockPosition::Left) + center.width + dock_size(DockPosition::Right),
+            center.height + dock_size(DockPosition::Bottom),
+        )
+    }
 
-            for removed_item in pane.read(cx).items() {
-                self.panes_by_item.remove(&removed_item.item_id());
-            }
+    /// The bounds the workspace was last laid out with, empty until it is first rendered.
+    pub fn bounds(&self) -> Bounds<Pixels> {
+        self.bounds
+    }
 
-            cx.notify();
-        }
+    /// Whether the workspace has been painted yet, so [`Workspace::bounds`] and the bounds
+    /// of the center panes are known. Navigation and resizing that depend on them do
+    /// nothing before then.
+    pub fn is_laid_out(&self) -> bool {
+        self.bounds.size.width > px(0.) && self.bounds.size.height > px(0.)
+    }
 
-        cx.emit(Event::PaneRemoved);
+    pub fn database_id(&self) -> Option<WorkspaceId> {
+        self.database_id
     }
 
-    fn force_remove_pane(
-        &mut self,
-        pane: &Entity<Pane>,
-        window: &mut Window,
-        cx: &mut Context<Workspace>,
-    ) {
-        self.panes.retain(|p| p != pane);
-        self.panes
-            .last()
-            .unwrap()
-            .update(cx, |pane, _| pane.focus(window));
-        if self.last_active_center_pane == Some(pane.downgrade()) {
-            self.last_active_center_pane = None;
-        }
+    pub fn handle_hitbox_size(&self) -> Pixels {
+        self.pane_group_options.handle_hitbox_size
+    }
+
+    /// Set the size of the hitbox used to grab the dividers between center panes.
+    ///
+    /// This is also the distance used to look up the neighboring pane when
+    /// navigating between panes by direction.
+    pub fn set_handle_hitbox_size(&mut self, size: Pixels, cx: &mut Context<Self>) {
+        self.pane_group_options.handle_hitbox_size = size;
         cx.notify();
     }
 
-    pub fn panes(&self) -> &[Entity<Pane>] {
-        &self.panes
+    pub fn divider_size(&self) -> Pixels {
+        self.pane_group_options.divider_size
     }
 
-    pub fn active_pane(&self) -> &Entity<Pane> {
-        &self.active_pane
+    /// Set the thickness of the dividers painted between center panes.
+    ///
+    /// The dividers stay grabbable anywhere within [`Workspace::handle_hitbox_size`].
+    pub fn set_divider_size(&mut self, size: Pixels, cx: &mut Context<Self>) {
+        self.pane_group_options.divider_size = size;
+        cx.notify();
     }
 
-    // pub fn reopen_closed_item(&mut self, window: &mut Window, cx: &mut Context<Workspace>) -> Task<Result<()>> {
-    //     self.navigate_history(
-    //         self.active_pane().downgrade(),
-    //         NavigationMode::ReopeningClosedItem,
-    //         cx,
-    //     )
-    // }
+    pub fn divider_color(&self) -> Option<Hsla> {
+        self.pane_group_options.divider_color
+    }
 
-    fn activate_pane_at_index(
-        &mut self,
-        action: &ActivatePane,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let panes = self.center.panes();
-        if let Some(pane) = panes.get(action.0).map(|p| (*p).clone()) {
-            cx.focus_view(&pane, window);
-        } else {
-            self.split_and_clone(self.active_pane.clone(), SplitDirection::Right, window, cx);
-        }
+    /// Override the color of the dividers between center panes, `None` uses the theme border.
+    pub fn set_divider_color(&mut self, color: Option<Hsla>, cx: &mut Context<Self>) {
+        self.pane_group_options.divider_color = color;
+        cx.notify();
     }
 
-    pub fn activate_next_pane(&mut self, window: &mut Window, cx: &mut App) {
-        let panes = self.center.panes();
-        if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
-            let next_ix = (ix + 1) % panes.len();
-            let next_pane = panes[next_ix].clone();
-            window.focus(&next_pane.focus_handle(cx));
-        }
+    pub fn max_pane_flex_ratio(&self) -> Option<f32> {
+        self.pane_group_options.max_flex_ratio
     }
 
-    pub fn activate_previous_pane(&mut self, window: &mut Window, cx: &mut App) {
-        let panes = self.center.panes();
-        if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
-            let prev_ix = cmp::min(ix.wrapping_sub(1), panes.len() - 1);
-            let prev_pane = panes[prev_ix].clone();
-            window.focus(&prev_pane.focus_handle(cx));
+    /// Limit the fraction of an axis a single center pane can be resized to take,
+    /// e.g. `Some(0.8)` keeps its siblings from being squeezed to their minimum size.
+    pub fn set_max_pane_flex_ratio(&mut self, ratio: Option<f32>, cx: &mut Context<Self>) {
+        self.pane_group_options.max_flex_ratio = ratio;
+        cx.notify();
+    }
+
+    pub fn focus_follows_mouse(&self) -> bool {
+        self.focus_follows_mouse
+    }
+
+    /// Focus a center pane when the mouse rests on it, without clicking. Disabled by default.
+    pub fn set_focus_follows_mouse(&mut self, focus_follows_mouse: bool) {
+        self.focus_follows_mouse = focus_follows_mouse;
+        if !focus_follows_mouse {
+            self.focus_on_hover_task = None;
         }
     }
 
-    pub fn activate_pane_in_direction(
+    /// Called when the mouse moves over `pane`. With focus-follows-mouse enabled, this
+    /// focuses the pane once the mouse has stayed on it for a moment, so moving across
+    /// panes doesn't change focus on the way.
+    ///
+    /// `pane_focus_handle` is passed in since the pane is being updated.
+    pub(crate) fn pane_hovered(
         &mut self,
-        direction: SplitDirection,
+        pane: &Entity<Pane>,
+        pane_focus_handle: &FocusHandle,
         window: &mut Window,
-        cx: &mut App,
+        cx: &mut Context<Self>,
     ) {
-        use ActivateInDirectionTarget as Target;
-        enum Origin {
-            LeftDock,
-            RightDock,
-            BottomDock,
-            Center,
+        if !self.focus_follows_mouse {
+            return;
+        }
+        if pane_focus_handle.contains_focused(window, cx) {
+            self.focus_on_hover_task = None;
+            return;
+        }
+        if self
+            .focus_on_hover_task
+            .as_ref()
+            .is_some_and(|(hovered, _)| *hovered == pane.downgrade())
+        {
+            return;
         }
 
-        let origin: Origin = [
-            (&self.left_dock, Origin::LeftDock),
-            (&self.right_dock, Origin::RightDock),
-            (&self.bottom_dock, Origin::BottomDock),
-        ]
-        .into_iter()
-        .find_map(|(dock, origin)| {
-            if dock.focus_handle(cx).contains_focused(window, cx) && dock.read(cx).is_open() {
-                Some(origin)
-            } else {
-                None
+        let hovered = pane.downgrade();
+        let task = cx.spawn_in(window, {
+            let hovered = hovered.clone();
+            |this, mut cx| async move {
+                cx.background_executor()
+                    .timer(FOCUS_FOLLOWS_MOUSE_DELAY)
+                    .await;
+                this.update_in(&mut cx, |this, window, cx| {
+                    this.focus_on_hover_task = None;
+                    let Some(pane) = hovered.upgrade() else {
+                        return;
+                    };
+                    // Focusing the pane emits `pane::Event::Focus` like a click would
+                    if this.panes.contains(&pane) {
+                        window.focus(&pane.focus_handle(cx));
+                    }
+                })
+                .log_err();
             }
-        })
-        .unwrap_or(Origin::Center);
+        });
+        self.focus_on_hover_task = Some((hovered, task));
+    }
 
-        let get_last_active_pane = || {
-            self.last_active_center_pane.as_ref().and_then(|p| {
-                let p = p.upgrade()?;
-                (p.read(cx).items_len() != 0).then_some(p)
-            })
-        };
+    pub fn highlight_active_pane(&self) -> bool {
+        self.pane_group_options.highlight_active_pane
+    }
 
-        let try_dock =
-            |dock: &Entity<Dock>| dock.read(cx).is_open().then(|| Target::Dock(dock.clone()));
+    /// Outline the active center pane when there is more than one, enabled by default.
+    pub fn set_highlight_active_pane(&mut self, highlight: bool, cx: &mut Context<Self>) {
+        self.pane_group_options.highlight_active_pane = highlight;
+        cx.notify();
+    }
 
-        let target = match (origin, direction) {
-            // We're in the center, so we first try to go to a different pane,
-            // otherwise try to go to a dock.
-            (Origin::Center, direction) => {
-                if let Some(pane) = self.find_pane_in_direction(direction, window, cx) {
-                    Some(Target::Pane(pane))
-                } else {
-                    match direction {
-                        SplitDirection::Up => None,
-                        SplitDirection::Down => try_dock(&self.bottom_dock),
-                        SplitDirection::Left => try_dock(&self.left_dock),
-                        SplitDirection::Right => try_dock(&self.right_dock),
-                    }
-                }
-            }
+    pub fn bounds_save_debounce(&self) -> Option<Duration> {
+        self.bounds_save_debounce
+    }
 
-            (Origin::LeftDock, SplitDirection::Right) => {
-                if let Some(last_active_pane) = get_last_active_pane() {
-                    Some(Target::Pane(last_active_pane))
-                } else {
-                    try_dock(&self.bottom_dock).or_else(|| try_dock(&self.right_dock))
-                }
-            }
+    /// Set how long window bounds changes are collected before saving them, `None` saves
+    /// on the next tick after each change. Defaults to 100ms.
+    pub fn set_bounds_save_debounce(&mut self, debounce: Option<Duration>) {
+        self.bounds_save_debounce = debounce;
+    }
+
+    pub fn dock_resize_snap(&self) -> Option<&DockResizeSnap> {
+        self.dock_resize_snap.as_ref()
+    }
 
-            (Origin::LeftDock, SplitDirection::Down)
-            | (Origin::RightDock, SplitDirection::Down) => try_dock(&self.bottom_dock),
+    /// Set the snap points used while resizing a dock by dragging, or `None` to disable snapping.
+    pub fn set_dock_resize_snap(&mut self, snap: Option<DockResizeSnap>) {
+        self.dock_resize_snap = snap;
+    }
 
-            (Origin::BottomDock, SplitDirection::Up) => get_last_active_pane().map(Target::Pane),
-            (Origin::BottomDock, SplitDirection::Left) => try_dock(&self.left_dock),
-            (Origin::BottomDock, SplitDirection::Right) => try_dock(&self.right_dock),
+    pub fn dock_drag_threshold(&self) -> Pixels {
+        self.dock_drag_threshold
+    }
 
-            (Origin::RightDock, SplitDirection::Left) => {
-                if let Some(last_active_pane) = get_last_active_pane() {
-                    Some(Target::Pane(last_active_pane))
-                } else {
-                    try_dock(&self.bottom_dock).or_else(|| try_dock(&self.left_dock))
-                }
-            }
+    /// Set how far the mouse has to move from where it was pressed before dragging a dock
+    /// edge starts resizing the dock, so a slightly shaky click doesn't resize it. Zero,
+    /// the default, resizes on the first movement.
+    pub fn set_dock_drag_threshold(&mut self, threshold: Pixels) {
+        self.dock_drag_threshold = threshold.max(px(0.));
+    }
+
+    pub fn bottom_dock_full_width(&self) -> bool {
+        self.bottom_dock_full_width
+    }
+
+    /// Lay the bottom dock out below the side docks across the whole window, instead of
+    /// only below the center panes.
+    pub fn set_bottom_dock_full_width(&mut self, full_width: bool, cx: &mut Context<Self>) {
+        self.bottom_dock_full_width = full_width;
+        cx.notify();
+    }
+
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.layout_direction
+    }
+
+    /// Lay the workspace out right to left, e.g. for RTL locales. This shows the left dock
+    /// on the right of the window and the right dock on the left, and flips which way
+    /// [`SplitDirection::Left`] and [`SplitDirection::Right`] move between center panes.
+    pub fn set_layout_direction(&mut self, direction: LayoutDirection, cx: &mut Context<Self>) {
+        if self.layout_direction == direction {
+            return;
+        }
+        self.layout_direction = direction;
+        self.left_dock.update(cx, |_, cx| cx.notify());
+        self.right_dock.update(cx, |_, cx| cx.notify());
+        cx.notify();
+    }
+
+    pub fn dock_gutter_size(&self) -> Option<Pixels> {
+        self.dock_gutter_size
+    }
+
+    /// Show a visible gutter of the given thickness between each open dock and the center,
+    /// which can be dragged to resize the dock. `None`, the default, leaves only the
+    /// invisible resize handle on the dock's edge.
+    pub fn set_dock_gutter_size(&mut self, size: Option<Pixels>, cx: &mut Context<Self>) {
+        self.dock_gutter_size = size;
+        cx.notify();
+    }
 
-            _ => None,
+    fn render_dock_gutter(&self, position: DockPosition, cx: &App) -> Option<impl IntoElement> {
+        let gutter_size = self.dock_gutter_size?;
+        if self.zoomed_position == Some(position) || !self.dock_at(position).read(cx).is_open() {
+            return None;
+        }
+
+        let id = match position {
+            DockPosition::Left => "left-dock-gutter",
+            DockPosition::Bottom => "bottom-dock-gutter",
+            DockPosition::Right => "right-dock-gutter",
         };
+        Some(
+            div()
+                .id(id)
+                .flex_none()
+                .bg(cx.theme().border)
+                .map(|this| match position.axis() {
+                    Axis::Horizontal => this.w(gutter_size).h_full().cursor_col_resize(),
+                    Axis::Vertical => this.h(gutter_size).w_full().cursor_row_resize(),
+                })
+                .on_drag(DraggedDock(position), |dock, _, _, cx| {
+                    cx.stop_propagation();
+                    cx.new(|_| dock.clone())
+                })
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+        )
+    }
 
-        match target {
-            Some(ActivateInDirectionTarget::Pane(pane)) => window.focus(&pane.focus_handle(cx)),
-            Some(ActivateInDirectionTarget::Dock(dock)) => {
-                if let Some(panel) = dock.read(cx).active_panel() {
-                    panel.panel_focus_handle(cx).focus(window);
-                } else {
-                    log::error!("Could not find a focus target when in switching focus in {direction} direction for a {:?} dock", dock.read(cx).position());
-                }
-            }
-            None => {}
+    pub fn split_empty_panes(&self) -> bool {
+        self.split_empty_panes
+    }
+
+    /// Whether [`Workspace::split_and_clone`] splits a pane without items into a new empty
+    /// pane, e.g. to have a split to drag items into. Splitting an empty pane does nothing
+    /// by default.
+    pub fn set_split_empty_panes(&mut self, split_empty_panes: bool) {
+        self.split_empty_panes = split_empty_panes;
+    }
+
+    pub fn layout_locked(&self) -> bool {
+        self.layout_locked
+    }
+
+    /// Freeze the layout, e.g. for a presentation mode. While locked, panes aren't split,
+    /// removed, swapped or resized, and docks aren't resized.
+    ///
+    /// Closing the last item of a pane leaves it empty instead of removing it.
+    pub fn set_layout_locked(&mut self, locked: bool, cx: &mut Context<Self>) {
+        self.layout_locked = locked;
+        cx.notify();
+    }
+
+    pub fn trap_focus(&self) -> bool {
+        self.trap_focus
+    }
+
+    /// Set whether the workspace takes focus back whenever the window loses focus.
+    pub fn set_trap_focus(&mut self, trap_focus: bool) {
+        self.trap_focus = trap_focus;
+    }
+
+    pub fn close_window_when_empty(&self) -> bool {
+        self.close_window_when_empty
+    }
+
+    /// Close the window once the last item of the workspace is closed, in addition to
+    /// emitting [`Event::WorkspaceEmpty`].
+    pub fn set_close_window_when_empty(&mut self, close_window_when_empty: bool) {
+        self.close_window_when_empty = close_window_when_empty;
+    }
+
+    /// Returns the position of the dock currently being resized by dragging, if any.
+    ///
+    /// Items can use this to ignore hover and selection while a dock is resized.
+    pub fn resizing_dock(&self) -> Option<DockPosition> {
+        self.resizing_dock
+    }
+
+    fn end_dock_drag(&mut self, cx: &mut Context<Self>) {
+        self.mouse_press_position = None;
+        if self.resizing_dock.take().is_some() {
+            cx.notify();
         }
     }
 
-    pub fn find_pane_in_direction(
+    fn resize_dock(
         &mut self,
-        direction: SplitDirection,
-        _window: &Window,
-        cx: &App,
-    ) -> Option<Entity<Pane>> {
-        let bounding_box = self.center.bounding_box_for_pane(&self.active_pane)?;
-        let cursor = self.active_pane.read(cx).pixel_position_of_cursor(cx);
-        let center = match cursor {
-            Some(cursor) if bounding_box.contains(&cursor) => cursor,
-            _ => bounding_box.center(),
+        position: DockPosition,
+        size: Pixels,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.layout_locked {
+            return;
+        }
+        let total = match position.axis() {
+            Axis::Horizontal => self.bounds.size.width,
+            Axis::Vertical => self.bounds.size.height,
+        };
+        // Snap points are relative to the workspace size, which is unknown until it's laid out
+        let size = match &self.dock_resize_snap {
+            Some(snap) if self.is_laid_out() => snap.snap(size, total),
+            _ => Some(size),
         };
 
-        let distance_to_next = pane_group::HANDLE_HITBOX_SIZE;
-
-        let target = match direction {
-            SplitDirection::Left => {
-                Point::new(bounding_box.left() - distance_to_next.into(), center.y)
-            }
-            SplitDirection::Right => {
-                Point::new(bounding_box.right() + distance_to_next.into(), center.y)
-            }
-            SplitDirection::Up => {
-                Point::new(center.x, bounding_box.top() - distance_to_next.into())
+        let dock = self.dock_at(position);
+        let mut focus_center = false;
+        let was_open = dock.read(cx).is_open();
+        let is_open = dock.update(cx, |dock, cx| match size {
+            Some(size) => {
+                // Dragging back past the hide threshold reopens a dock that was snapped closed.
+                dock.set_open(true, window, cx);
+                dock.resize_active_panel(Some(size), window, cx);
+                true
             }
-            SplitDirection::Down => {
-                Point::new(center.x, bounding_box.bottom() + distance_to_next.into())
+            None => {
+                if let Some(active_panel) = dock.active_panel() {
+                    focus_center = active_panel
+                        .panel_focus_handle(cx)
+                        .contains_focused(window, cx);
+                }
+                dock.set_open(false, window, cx);
+                false
             }
+        });
+
+        if is_open != was_open {
+            cx.emit(Event::DockStateChanged {
+                position,
+                open: is_open,
+            });
+        }
+
+        if focus_center {
+            self.active_pane.update(cx, |pane, _| pane.focus(window))
+        }
+    }
+
+    fn add_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Pane> {
+        let workspace = self.weak_handle();
+        let pane = match &self.pane_factory {
+            Some(pane_factory) => cx.new(|cx| pane_factory(workspace, window, cx)),
+            None => cx.new(|cx| Pane::new(workspace, None, window, cx)),
         };
-        self.center.pane_at_pixel_position(target).cloned()
+        let serialized_id = SerializedPaneId(self.next_serialized_pane_id);
+        self.next_serialized_pane_id += 1;
+        pane.update(cx, |pane, _| pane.set_serialized_id(serialized_id));
+        if let Some(empty_pane_view) = self.empty_pane_view.clone() {
+            pane.update(cx, |pane, cx| {
+                pane.set_empty_view(Some(empty_pane_view), cx)
+            });
+        }
+        cx.subscribe_in(&pane, window, Self::handle_pane_event)
+            .detach();
+        self.panes.push(pane.clone());
+        cx.focus_view(&pane, window);
+        cx.emit(Event::PaneAdded(pane.clone()));
+        pane
     }
 
-    pub fn swap_pane_in_direction(
+    /// Split `pane_to_split` in `split_direction` into a new empty pane.
+    ///
+    /// `ratio` is the share of the split pane's space the new pane takes, see
+    /// [`PaneGroup::split_with_ratio`]; `None` for half of it.
+    ///
+    /// Returns `None` if the layout is locked or `pane_to_split` isn't in the center group
+    /// anymore.
+    pub fn split_pane(
         &mut self,
-        direction: SplitDirection,
+        pane_to_split: Entity<Pane>,
+        split_direction: SplitDirection,
+        ratio: Option<f32>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
-        if let Some(to) = self.find_pane_in_direction(direction, window, cx) {
-            self.center.swap(&self.active_pane.clone(), &to);
-            cx.notify();
+    ) -> Option<Entity<Pane>> {
+        if self.layout_locked {
+            return None;
         }
+        let new_pane = self.add_pane(window, cx);
+        let new_pane =
+            self.insert_split(&pane_to_split, new_pane, split_direction, ratio, window, cx);
+        self.notify_layout_changed(cx);
+        new_pane
     }
 
-    fn handle_pane_focused(
+    /// Add `new_pane` to the center group by splitting `pane` in `direction`.
+    ///
+    /// If `pane` isn't in the center anymore, e.g. it was removed before an event to split
+    /// it was handled, `new_pane` is discarded instead and its items are moved to the active
+    /// pane.
+    fn insert_split(
         &mut self,
-        pane: Entity<Pane>,
+        pane: &Entity<Pane>,
+        new_pane: Entity<Pane>,
+        direction: SplitDirection,
+        ratio: Option<f32>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
-        if self.active_pane != pane {
-            self.active_pane = pane.clone();
-            self.last_active_center_pane = Some(pane.downgrade());
-        }
-
-        self.dismiss_zoomed_items_to_reveal(None, window, cx);
-        if pane.read(cx).is_zoomed() {
-            self.zoomed = Some(pane.downgrade().into());
-        } else {
-            self.zoomed = None;
+    ) -> Option<Entity<Pane>> {
+        self.push_pane_layout_history();
+        if let Err(error) = self
+            .center
+            .split_with_ratio(pane, &new_pane, direction, ratio)
+        {
+            log::error!("failed to split pane: {error}");
+            self.pane_layout_history.pop_back();
+            self.panes.retain(|p| p != &new_pane);
+            self.panes_by_recent_use
+                .retain(|p| p != &new_pane.downgrade());
+
+            let fallback = if self.panes.contains(&self.active_pane) {
+                self.active_pane.clone()
+            } else {
+                self.center.first_pane()
+            };
+            let items = new_pane.read(cx).items().cloned().collect::<Vec<_>>();
+            new_pane.update(cx, |pane, cx| pane.set_items(Vec::new(), 0, window, cx));
+            fallback.update(cx, |pane, cx| {
+                for item in items {
+                    pane.add_item(item, true, true, None, window, cx);
+                }
+            });
+            cx.focus_view(&fallback, window);
+            cx.emit(Event::PaneRemoved);
+            return None;
         }
-        self.zoomed_position = None;
-        cx.emit(Event::ZoomChanged);
 
-        cx.notify();
+        self.sync_panes_with_center();
+        cx.emit(Event::PaneSplit {
+            source: pane.clone(),
+            new: new_pane.clone(),
+            direction,
+        });
+        Some(new_pane)
     }
 
-    fn handle_pane_event(
+    /// Split `pane` in `direction` into a new pane with a clone of its active item.
+    ///
+    /// `ratio` is the share of the split pane's space the new pane takes, like for
+    /// [`Workspace::split_pane`].
+    pub fn split_and_clone(
         &mut self,
-        pane: &Entity<Pane>,
-        event: &pane::Event,
+        pane: Entity<Pane>,
+        direction: SplitDirection,
+        ratio: Option<f32>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
-        match event {
-            pane::Event::AddItem { item } => {
-                item.added_to_pane(self, pane, window, cx);
-                cx.emit(Event::ItemAdded);
-            }
-            pane::Event::Split(direction) => {
-                self.split_and_clone(pane.clone(), *direction, window, cx);
-            }
-            pane::Event::Remove => self.remove_pane(pane, window, cx),
-            pane::Event::ActivateItem { local: _ } => {
-                cx.emit(Event::ActiveItemChanged);
-            }
-            pane::Event::ChangeItemTitle => {
-                cx.emit(Event::ActiveItemChanged);
+    ) -> Option<Entity<Pane>> {
+        if self.layout_locked {
+            return None;
+        }
+        let Some(item) = pane.read(cx).active_item() else {
+            if !self.split_empty_panes {
+                return None;
+            }
+            let new_pane = self.add_pane(window, cx);
+            let new_pane = self.insert_split(&pane, new_pane, direction, ratio, window, cx);
+            self.notify_layout_changed(cx);
+            return new_pane;
+        };
+        let maybe_pane_handle =
+            if let Some(clone) = item.clone_on_split(self.database_id(), window, cx) {
+                let new_pane = self.add_pane(window, cx);
+                new_pane.update(cx, |pane, cx| {
+                    pane.add_item(clone, true, true, None, window, cx)
+                });
+                self.insert_split(&pane, new_pane, direction, ratio, window, cx)
+            } else {
+                None
+            };
+        self.notify_layout_changed(cx);
+        maybe_pane_handle
+    }
+
+    /// Split `pane` in `direction` into a new pane with a clone of every item of `pane`
+    /// that supports [`Item::clone_on_split`], unlike [`Workspace::split_and_clone`] which
+    /// only clones the active item. The clones keep the tab order, and the clone of the
+    /// active item is active. Items that can't be cloned are left out.
+    ///
+    /// Returns `None` without splitting if none of the items can be cloned.
+    ///
+    /// [`Item::clone_on_split`]: crate::item::Item::clone_on_split
+    pub fn duplicate_pane(
+        &mut self,
+        pane: Entity<Pane>,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Pane>> {
+        if self.layout_locked {
+            return None;
+        }
+        let (items, active_item_index) = {
+            let pane = pane.read(cx);
+            let items = pane
+                .items()
+                .map(|item| item.boxed_clone())
+                .collect::<Vec<_>>();
+            (items, pane.active_item_index())
+        };
+
+        let mut clones = Vec::new();
+        let mut active_clone_index = None;
+        for (ix, item) in items.iter().enumerate() {
+            if let Some(clone) = item.clone_on_split(self.database_id(), window, cx) {
+                if ix == active_item_index {
+                    active_clone_index = Some(clones.len());
+                }
+                clones.push(clone);
+            }
+        }
+        if clones.is_empty() {
+            return None;
+        }
+
+        let new_pane = self.add_pane(window, cx);
+        new_pane.update(cx, |new_pane, cx| {
+            for (ix, clone) in clones.into_iter().enumerate() {
+                new_pane.add_item(clone, false, false, Some(ix), window, cx);
+            }
+            new_pane.activate_item(active_clone_index.unwrap_or(0), true, true, window, cx);
+        });
+        let new_pane = self.insert_split(&pane, new_pane, direction, None, window, cx);
+        self.notify_layout_changed(cx);
+        new_pane
+    }
+
+    /// Split `pane` in `direction` and place the item with the given id in the new pane.
+    ///
+    /// The item is cloned into the new pane if it supports [`Item::clone_on_split`],
+    /// otherwise it is moved out of `pane`.
+    ///
+    /// [`Item::clone_on_split`]: crate::item::Item::clone_on_split
+    pub fn split_with_item(
+        &mut self,
+        pane: Entity<Pane>,
+        direction: SplitDirection,
+        item_id: EntityId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Pane>> {
+        if self.layout_locked {
+            return None;
+        }
+        let item = pane
+            .read(cx)
+            .items()
+            .find(|item| item.item_id() == item_id)?
+            .boxed_clone();
+
+        let new_pane = self.add_pane(window, cx);
+        if let Some(clone) = item.clone_on_split(self.database_id(), window, cx) {
+            new_pane.update(cx, |pane, cx| {
+                pane.add_item(clone, true, true, None, window, cx)
+            });
+        } else {
+            self.move_item(pane.clone(), new_pane.clone(), item_id, 0, window, cx);
+        }
+        let new_pane = self.insert_split(&pane, new_pane, direction, None, window, cx);
+        self.notify_layout_changed(cx);
+        new_pane
+    }
+
+    pub fn split_pane_with_item(
+        &mut self,
+        pane_to_split: WeakEntity<Pane>,
+        split_direction: SplitDirection,
+        from: WeakEntity<Pane>,
+        item_id_to_move: EntityId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pane_to_split) = pane_to_split.upgrade() else {
+            return;
+        };
+        let Some(from) = from.upgrade() else {
+            return;
+        };
+
+        self.split_pane_with_item_internal(
+            pane_to_split,
+            split_direction,
+            from,
+            item_id_to_move,
+            true,
+            window,
+            cx,
+        );
+    }
+
+    /// Split the active pane in `direction` and move its active item into the new pane,
+    /// keeping the active pane focused.
+    ///
+    /// Does nothing if the active pane has a single item, as moving it would close the pane.
+    pub fn send_item_to_new_split(
+        &mut self,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Pane>> {
+        let pane = self.active_pane.clone();
+        if pane.read(cx).items_len() < 2 {
+            return None;
+        }
+        let item_id = pane.read(cx).active_item()?.item_id();
+
+        self.split_pane_with_item_internal(
+            pane.clone(),
+            direction,
+            pane,
+            item_id,
+            false,
+            window,
+            cx,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn split_pane_with_item_internal(
+        &mut self,
+        pane_to_split: Entity<Pane>,
+        split_direction: SplitDirection,
+        from: Entity<Pane>,
+        item_id_to_move: EntityId,
+        focus_new_pane: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Pane>> {
+        if self.layout_locked {
+            return None;
+        }
+        let new_pane = self.add_pane(window, cx);
+        self.move_item(
+            from.clone(),
+            new_pane.clone(),
+            item_id_to_move,
+            0,
+            window,
+            cx,
+        );
+        let new_pane =
+            self.insert_split(&pane_to_split, new_pane, split_direction, None, window, cx);
+        if new_pane.is_some() && !focus_new_pane {
+            from.update(cx, |pane, _| pane.focus(window));
+        }
+        self.notify_layout_changed(cx);
+        new_pane
+    }
+
+    pub fn move_item(
+        &mut self,
+        source: Entity<Pane>,
+        destination: Entity<Pane>,
+        item_id_to_move: EntityId,
+        destination_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((item_ix, item_handle)) = source
+            .read(cx)
+            .items()
+            .enumerate()
+            .find(|(_, item_handle)| item_handle.item_id() == item_id_to_move)
+        else {
+            // Tab was closed during drag
+            return;
+        };
+
+        let item_handle = item_handle.clone();
+
+        if source != destination {
+            // Close item from previous pane
+            source.update(cx, |source, cx| {
+                source.remove_item(item_ix, false, window, cx);
+            });
+        }
+
+        // This automatically removes duplicate items in the pane
+        destination.update(cx, |destination, cx| {
+            destination.add_item(item_handle, true, true, Some(destination_index), window, cx);
+            destination.focus(window)
+        });
+
+        if source != destination {
+            cx.emit(Event::ItemMoved {
+                item_id: item_id_to_move,
+                from: source.downgrade(),
+                to: destination.downgrade(),
+            });
+        } else if let Some(index) = destination
+            .read(cx)
+            .index_for_item_id(item_id_to_move)
+            .filter(|index| *index != item_ix)
+        {
+            cx.emit(Event::ItemReordered {
+                item_id: item_id_to_move,
+                pane: destination.downgrade(),
+                index,
+            });
+        }
+    }
+
+    /// Remove the item with the given id from its pane and return it, e.g. to open it in
+    /// another workspace. A pane left empty is removed like when its last item is closed.
+    ///
+    /// Returns `None` if the item is not open in any pane.
+    pub fn take_item(
+        &mut self,
+        item_id: EntityId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Box<dyn ItemHandle>> {
+        let pane = self.pane_for_item_id(item_id).or_else(|| {
+            self.panes
+                .iter()
+                .find(|pane| pane.read(cx).index_for_item_id(item_id).is_some())
+                .cloned()
+        })?;
+        let item_ix = pane.read(cx).index_for_item_id(item_id)?;
+        let item = pane.read(cx).items().nth(item_ix)?.boxed_clone();
+
+        pane.update(cx, |pane, cx| pane.remove_item(item_ix, false, window, cx));
+        self.panes_by_item.remove(&item_id);
+        Some(item)
+    }
+
+    /// Customize how panes created from now on are constructed, e.g. to pass a
+    /// `can_drop_predicate` or configure them with [`Pane::set_can_split`].
+    ///
+    /// The pane the workspace starts with is built before this can be set.
+    pub fn set_pane_factory(
+        &mut self,
+        pane_factory: impl Fn(WeakEntity<Self>, &mut Window, &mut Context<Pane>) -> Pane + 'static,
+    ) {
+        self.pane_factory = Some(Box::new(pane_factory));
+    }
+
+    /// Set what center panes show while they have no items, e.g. a "drag a tab here" hint.
+    pub fn set_empty_pane_view(
+        &mut self,
+        empty_pane_view: impl Fn(&mut Window, &mut App) -> AnyElement + 'static,
+        cx: &mut Context<Self>,
+    ) {
+        let empty_pane_view: Rc<dyn Fn(&mut Window, &mut App) -> AnyElement> =
+            Rc::new(empty_pane_view);
+        self.empty_pane_view = Some(empty_pane_view.clone());
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| {
+                pane.set_empty_view(Some(empty_pane_view.clone()), cx)
+            });
+        }
+    }
+
+    /// Register a predicate consulted before a pane is removed from the center group.
+    ///
+    /// If any predicate returns `false` the pane is kept, only its unpinned items are closed.
+    pub fn register_can_remove_pane(
+        &mut self,
+        predicate: impl Fn(&Entity<Pane>, &App) -> bool + 'static,
+    ) {
+        self.can_remove_pane.push(Box::new(predicate));
+    }
+
+    fn remove_pane(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
+        if self.layout_locked {
+            return;
+        }
+        if !self
+            .can_remove_pane
+            .iter()
+            .all(|can_remove| can_remove(pane, cx))
+        {
+            if pane.read(cx).items_len() > 0 {
+                pane.update(cx, |pane, cx| {
+                    pane.close_unpinned_items(window, cx, |_| true)
+                        .detach_and_log_err(cx)
+                });
+            }
+            return;
+        }
+
+        let previous_center = self.center.deep_clone();
+        let Ok(removed) = self.center.remove(pane) else {
+            // Already gone from the center, e.g. by an undone or applied layout
+            return;
+        };
+        if removed {
+            self.record_pane_layout(previous_center, self.active_pane.clone());
+            self.force_remove_pane(pane, window, cx);
+            self.sync_panes_with_center();
+
+            if self.zoomed == Some(pane.downgrade().into()) {
+                self.zoomed = None;
+                self.zoomed_position = None;
+                cx.emit(Event::ZoomChanged);
+            }
+
+            for removed_item in pane.read(cx).items() {
+                self.panes_by_item.remove(&removed_item.item_id());
+            }
+
+            self.notify_layout_changed(cx);
+        }
+
+        cx.emit(Event::PaneRemoved);
+    }
+
+    /// Make `panes` hold exactly the panes of the center group, once each.
+    ///
+    /// The two are updated separately by structural changes and navigation relies on both,
+    /// so repair any drift rather than letting them disagree.
+    fn sync_panes_with_center(&mut self) {
+        let center_panes = self.center.panes();
+        let mut panes = Vec::with_capacity(center_panes.len());
+        for pane in self.panes.iter().chain(center_panes.iter().copied()) {
+            if center_panes.contains(&pane) && !panes.contains(pane) {
+                panes.push(pane.clone());
+            }
+        }
+
+        if panes != self.panes {
+            log::warn!(
+                "workspace panes were out of sync with the center group, {} -> {} panes",
+                self.panes.len(),
+                panes.len()
+            );
+            self.panes = panes;
+        }
+    }
+
+    fn force_remove_pane(
+        &mut self,
+        pane: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        self.panes.retain(|p| p != pane);
+        self.panes_by_recent_use.retain(|p| p != &pane.downgrade());
+        if self.last_active_center_pane == Some(pane.downgrade()) {
+            self.last_active_center_pane = None;
+        }
+
+        if self.panes.is_empty() {
+            // `panes` got out of sync with the center, fall back to the panes it still holds.
+            self.sync_panes_with_center();
+            self.panes.retain(|p| p != pane);
+        }
+
+        match self.panes.last() {
+            Some(last_pane) => last_pane.update(cx, |pane, _| pane.focus(window)),
+            None => {
+                // The center must always hold a pane, start over with an empty one if it
+                // has none left.
+                let new_pane = self.add_pane(window, cx);
+                self.center = PaneGroup::new(new_pane.clone());
+                self.active_pane = new_pane.clone();
+                self.last_active_center_pane = Some(new_pane.downgrade());
+            }
+        }
+    }
+
+    pub fn panes(&self) -> &[Entity<Pane>] {
+        &self.panes
+    }
+
+    /// The panes the user can currently see, unlike [`Workspace::panes`]: just the zoomed
+    /// pane while one is zoomed, otherwise every center pane in layout order.
+    ///
+    /// With `include_docks`, the panels shown by open docks are added too when they are
+    /// panes. A zoomed dock panel hides the center, so then only that panel can be returned.
+    pub fn visible_panes(&self, include_docks: bool, cx: &App) -> Vec<Entity<Pane>> {
+        if let Some(zoomed) = self.zoomed.as_ref().and_then(|zoomed| zoomed.upgrade()) {
+            if self.zoomed_position.is_some() && !include_docks {
+                return Vec::new();
+            }
+            return zoomed.downcast::<Pane>().ok().into_iter().collect();
+        }
+
+        let mut panes: Vec<_> = self.center.panes().into_iter().cloned().collect();
+        if include_docks {
+            panes.extend(self.docks().into_iter().filter_map(|(_, dock)| {
+                dock.read(cx)
+                    .visible_panel()?
+                    .to_any()
+                    .downcast::<Pane>()
+                    .ok()
+            }));
+        }
+        panes
+    }
+
+    pub fn active_pane(&self) -> &Entity<Pane> {
+        &self.active_pane
+    }
+
+    /// Set the user-visible label of `pane`, e.g. to list panes by name in a palette.
+    pub fn set_pane_label(
+        &mut self,
+        pane: &Entity<Pane>,
+        label: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let label = label.into();
+        pane.update(cx, |pane, cx| pane.set_label(Some(label), cx));
+    }
+
+    pub fn pane_label(&self, pane: &Entity<Pane>, cx: &App) -> Option<SharedString> {
+        pane.read(cx).display_label(cx)
+    }
+
+    /// The first center pane whose [`Pane::display_label`] is `label`.
+    pub fn pane_for_label(&self, label: &str, cx: &App) -> Option<Entity<Pane>> {
+        self.panes
+            .iter()
+            .find(|pane| pane.read(cx).display_label(cx).as_deref() == Some(label))
+            .cloned()
+    }
+
+    /// The most recently focused center pane other than `pane`, e.g. to open an item
+    /// "in the other pane".
+    pub fn most_recently_used_pane_excluding(&self, pane: &Entity<Pane>) -> Option<Entity<Pane>> {
+        self.panes_by_recent_use
+            .iter()
+            .filter_map(|p| p.upgrade())
+            .find(|p| p != pane && self.panes.contains(p))
+    }
+
+    pub fn active_item(&self, cx: &App) -> Option<Box<dyn ItemHandle>> {
+        self.active_pane.read(cx).active_item()
+    }
+
+    pub fn active_item_as<I: 'static>(&self, cx: &App) -> Option<Entity<I>> {
+        self.active_item(cx)?.downcast()
+    }
+
+    /// The center pane with the given [`SerializedPaneId`], e.g. to find a pane of a
+    /// restored layout.
+    pub fn pane_for_serialized_id(&self, id: SerializedPaneId, cx: &App) -> Option<Entity<Pane>> {
+        self.panes
+            .iter()
+            .find(|pane| pane.read(cx).serialized_id() == id)
+            .cloned()
+    }
+
+    pub fn pane_for_item_id(&self, item_id: EntityId) -> Option<Entity<Pane>> {
+        self.panes_by_item.get(&item_id)?.upgrade()
+    }
+
+    /// Focus the pane containing the given item and activate the item within it.
+    ///
+    /// Returns `false` if the item is no longer open in any pane.
+    pub fn activate_item(
+        &mut self,
+        item_id: EntityId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(pane) = self.pane_for_item_id(item_id) else {
+            return false;
+        };
+        let Some(ix) = pane.read(cx).index_for_item_id(item_id) else {
+            return false;
+        };
+
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(ix, true, true, window, cx)
+        });
+        true
+    }
+
+    // pub fn reopen_closed_item(&mut self, window: &mut Window, cx: &mut Context<Workspace>) -> Task<Result<()>> {
+    //     self.navigate_history(
+    //         self.active_pane().downgrade(),
+    //         NavigationMode::ReopeningClosedItem,
+    //         cx,
+    //     )
+    // }
+
+    fn activate_pane_at_index(
+        &mut self,
+        action: &ActivatePane,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let panes = match self.pane_index_order {
+            PaneOrder::Tree => self.center.panes().into_iter().cloned().collect(),
+            PaneOrder::Visual => self.panes_in_visual_order(),
+        };
+        if let Some(pane) = panes.get(action.0).cloned() {
+            cx.focus_view(&pane, window);
+        } else {
+            self.split_and_clone(
+                self.active_pane.clone(),
+                SplitDirection::Right,
+                None,
+                window,
+                cx,
+            );
+        }
+    }
+
+    /// The items of each center pane, in tab order, with the panes in visual order like
+    /// [`Workspace::panes_in_visual_order`].
+    pub fn grouped_items(&self, cx: &App) -> Vec<(Entity<Pane>, Vec<Box<dyn ItemHandle>>)> {
+        self.panes_in_visual_order()
+            .into_iter()
+            .map(|pane| {
+                let items = pane.read(cx).items().cloned().collect();
+                (pane, items)
+            })
+            .collect()
+    }
+
+    /// The center panes sorted top to bottom, then left to right, by their last rendered bounds.
+    pub fn panes_in_visual_order(&self) -> Vec<Entity<Pane>> {
+        let mut panes = self
+            .center
+            .panes()
+            .into_iter()
+            .map(|pane| (self.center.bounding_box_for_pane(pane), pane.clone()))
+            .collect::<Vec<_>>();
+        panes.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => (a.top(), a.left())
+                .partial_cmp(&(b.top(), b.left()))
+                .unwrap_or(cmp::Ordering::Equal),
+            // Panes not rendered yet go last, keeping their tree order.
+            (Some(_), None) => cmp::Ordering::Less,
+            (None, Some(_)) => cmp::Ordering::Greater,
+            (None, None) => cmp::Ordering::Equal,
+        });
+        panes.into_iter().map(|(_, pane)| pane).collect()
+    }
+
+    /// Activate the next (or previous) item, moving on to the next pane in visual order
+    /// past the last item of the active pane and wrapping around after the last pane.
+    pub fn activate_item_across_panes(
+        &mut self,
+        forward: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let items = self
+            .panes_in_visual_order()
+            .into_iter()
+            .flat_map(|pane| {
+                let len = pane.read(cx).items_len();
+                (0..len).map(move |ix| (pane.clone(), ix))
+            })
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            return;
+        }
+
+        let active_item_index = self.active_pane.read(cx).active_item_index();
+        let target_ix = match items
+            .iter()
+            .position(|(pane, ix)| pane == &self.active_pane && *ix == active_item_index)
+        {
+            Some(current_ix) if forward => (current_ix + 1) % items.len(),
+            Some(current_ix) => (current_ix + items.len() - 1) % items.len(),
+            None if forward => 0,
+            None => items.len() - 1,
+        };
+
+        let (pane, ix) = items[target_ix].clone();
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(ix, true, true, window, cx)
+        });
+    }
+
+    pub fn pane_index_order(&self) -> PaneOrder {
+        self.pane_index_order
+    }
+
+    /// Set the order used to look up the pane for [`ActivatePane`].
+    pub fn set_pane_index_order(&mut self, order: PaneOrder) {
+        self.pane_index_order = order;
+    }
+
+    pub fn activate_next_pane(&mut self, window: &mut Window, cx: &mut App) {
+        let panes = self.center.panes();
+        if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
+            let next_ix = (ix + 1) % panes.len();
+            let next_pane = panes[next_ix].clone();
+            window.focus(&next_pane.focus_handle(cx));
+        }
+    }
+
+    pub fn activate_previous_pane(&mut self, window: &mut Window, cx: &mut App) {
+        let panes = self.center.panes();
+        if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
+            let prev_ix = cmp::min(ix.wrapping_sub(1), panes.len() - 1);
+            let prev_pane = panes[prev_ix].clone();
+            window.focus(&prev_pane.focus_handle(cx));
+        }
+    }
+
+    /// Focus the pane that was focused before the active one. Repeating it toggles between
+    /// the two most recently focused panes.
+    pub fn activate_last_focused_pane(&mut self, window: &mut Window, cx: &mut App) {
+        if let Some(pane) = self.most_recently_used_pane_excluding(&self.active_pane) {
+            window.focus(&pane.focus_handle(cx));
+        }
+    }
+
+    /// The open dock containing the focused element, `None` when focus is in the center or
+    /// outside of the docks.
+    pub fn focused_dock(&self, window: &Window, cx: &App) -> Option<DockPosition> {
+        self.docks().into_iter().find_map(|(position, dock)| {
+            if dock.focus_handle(cx).contains_focused(window, cx) && dock.read(cx).is_open() {
+                Some(position)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn activate_pane_in_direction(
+        &mut self,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use ActivateInDirectionTarget as Target;
+
+        let focused_dock = self.focused_dock(window, cx);
+
+        // Only look for a neighboring pane when moving from the center
+        let pane_in_direction = if focused_dock.is_none() {
+            self.find_pane_in_direction(direction, window, cx)
+        } else {
+            None
+        };
+        let origin = match focused_dock {
+            Some(position) => NavOrigin::Dock(position),
+            None => NavOrigin::Center {
+                has_pane_in_direction: pane_in_direction.is_some(),
+            },
+        };
+
+        let last_active_pane = self.last_active_center_pane.as_ref().and_then(|p| {
+            let p = p.upgrade()?;
+            (p.read(cx).items_len() != 0).then_some(p)
+        });
+
+        let docks_open = DocksOpen {
+            left: self.left_dock.read(cx).is_open(),
+            right: self.right_dock.read(cx).is_open(),
+            bottom: self.bottom_dock.read(cx).is_open(),
+        };
+
+        let target = match resolve_navigation_target(
+            origin,
+            direction,
+            docks_open,
+            last_active_pane.is_some(),
+        ) {
+            NavTarget::PaneInDirection => pane_in_direction.map(Target::Pane),
+            NavTarget::LastActivePane => last_active_pane.map(Target::Pane),
+            NavTarget::Dock(position) => Some(Target::Dock(self.dock_at(position).clone())),
+            NavTarget::None => None,
+        };
+
+        match target {
+            Some(ActivateInDirectionTarget::Pane(pane)) => window.focus(&pane.focus_handle(cx)),
+            Some(ActivateInDirectionTarget::Dock(dock)) => {
+                if let Some(panel) = dock.read(cx).active_panel() {
+                    panel.panel_focus_handle(cx).focus(window);
+                } else {
+                    log::error!("Could not find a focus target when in switching focus in {direction} direction for a {:?} dock", dock.read(cx).position());
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// The center pane next to the active pane in `direction`, found from the last
+    /// rendered pane bounds. Always `None` before the workspace is first painted.
+    pub fn find_pane_in_direction(
+        &mut self,
+        direction: SplitDirection,
+        _window: &Window,
+        cx: &App,
+    ) -> Option<Entity<Pane>> {
+        if !self.is_laid_out() {
+            return None;
+        }
+        let bounding_box = self.center.bounding_box_for_pane(&self.active_pane)?;
+        if bounding_box.size.width <= px(0.) || bounding_box.size.height <= px(0.) {
+            return None;
+        }
+        let active_pane = self.active_pane.read(cx);
+        // Start from the cursor if it is in the pane content, e.g. not under a vertical tab bar
+        let anchor = active_pane.navigation_anchor(bounding_box, cx);
+        let center = match active_pane.pixel_position_of_cursor(cx) {
+            Some(cursor) if anchor.contains(&cursor) => cursor,
+            _ => anchor.center(),
+        };
+
+        let distance_to_next = self.pane_group_options.handle_hitbox_size;
+
+        let target = match self.layout_direction.visual_direction(direction) {
+            SplitDirection::Left => Point::new(bounding_box.left() - distance_to_next, center.y),
+            SplitDirection::Right => Point::new(bounding_box.right() + distance_to_next, center.y),
+            SplitDirection::Up => Point::new(center.x, bounding_box.top() - distance_to_next),
+            SplitDirection::Down => Point::new(center.x, bounding_box.bottom() + distance_to_next),
+        };
+        self.center.pane_at_pixel_position(target).cloned()
+    }
+
+    pub fn swap_pane_in_direction(
+        &mut self,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.layout_locked {
+            return;
+        }
+        if let Some(to) = self.find_pane_in_direction(direction, window, cx) {
+            self.swap_panes(&self.active_pane.clone(), &to, window, cx);
+            self.last_swap_direction = Some(direction);
+        }
+    }
+
+    /// Swap the places of two center panes, swapping a pane with itself does nothing.
+    pub fn swap_panes(
+        &mut self,
+        a: &Entity<Pane>,
+        b: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.layout_locked || a == b || !self.panes.contains(a) || !self.panes.contains(b) {
+            return;
+        }
+
+        self.push_pane_layout_history();
+        self.center.swap(a, b);
+        self.notify_layout_changed(cx);
+        self.serialize_workspace(window, cx);
+    }
+
+    /// Move a center pane within its split to where it was dropped at `position`, shifting
+    /// its siblings over instead of swapping, see [`PaneGroup::move_pane`].
+    pub fn move_pane_in_split(
+        &mut self,
+        pane: &Entity<Pane>,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.layout_locked || !self.panes.contains(pane) {
+            return false;
+        }
+
+        let previous_center = self.center.deep_clone();
+        let moved = self.center.move_pane(pane, position);
+        if moved {
+            self.record_pane_layout(previous_center, self.active_pane.clone());
+            self.notify_layout_changed(cx);
+            self.serialize_workspace(window, cx);
+        }
+        moved
+    }
+
+    /// Pull a center pane out of its nested split to the `direction` edge of the layout,
+    /// see [`PaneGroup::promote_pane_to_root`]. The pane and its items are kept.
+    pub fn promote_pane_to_root(
+        &mut self,
+        pane: &Entity<Pane>,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.layout_locked || !self.panes.contains(pane) {
+            return false;
+        }
+
+        let previous_center = self.center.deep_clone();
+        match self.center.promote_pane_to_root(pane, direction) {
+            Ok(()) => {
+                self.record_pane_layout(previous_center, self.active_pane.clone());
+                self.notify_layout_changed(cx);
+                self.pane_sizes_changed(window, cx);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Turn the split containing the active pane from side by side into stacked, or back.
+    pub fn rotate_pane_axis(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.layout_locked {
+            return;
+        }
+        let previous_center = self.center.deep_clone();
+        if self.center.rotate_axis(&self.active_pane) {
+            self.record_pane_layout(previous_center, self.active_pane.clone());
+            self.notify_layout_changed(cx);
+            self.pane_sizes_changed(window, cx);
+        }
+    }
+
+    /// Move a divider of the center group by `delta` without a mouse drag, like
+    /// [`PaneGroup::resize_split`], respecting the configured maximum flex ratio.
+    pub fn resize_split(
+        &mut self,
+        axis_path: &[usize],
+        divider_index: usize,
+        delta: Pixels,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if self.layout_locked {
+            return Err(anyhow!("Layout is locked"));
+        }
+        self.center.resize_split(
+            axis_path,
+            divider_index,
+            delta,
+            self.pane_group_options.max_flex_ratio,
+            cx,
+        )?;
+        self.notify_layout_changed(cx);
+        self.pane_sizes_changed(window, cx);
+        Ok(())
+    }
+
+    /// The center panes in the split `pane` is part of, see [`PaneGroup::panes_in_axis_of`].
+    pub fn panes_in_split_of(&self, pane: &Entity<Pane>) -> Vec<Entity<Pane>> {
+        self.center
+            .panes_in_axis_of(pane)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Resize the active pane's split so the pane sits in its middle, like
+    /// [`PaneGroup::center_pane`].
+    pub fn center_active_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if self.layout_locked {
+            return false;
+        }
+        let centered = self.center.center_pane(&self.active_pane, cx);
+        if centered {
+            self.notify_layout_changed(cx);
+            self.pane_sizes_changed(window, cx);
+        }
+        centered
+    }
+
+    /// Fold `pane` down to a thin strip in its split, like [`PaneGroup::collapse_pane`].
+    pub fn collapse_pane(
+        &mut self,
+        pane: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.layout_locked {
+            return false;
+        }
+        let collapsed = self.center.collapse_pane(pane);
+        if collapsed {
+            self.notify_layout_changed(cx);
+            self.pane_sizes_changed(window, cx);
+        }
+        collapsed
+    }
+
+    /// Unfold a pane collapsed by [`Workspace::collapse_pane`] back to its previous size.
+    pub fn expand_pane(
+        &mut self,
+        pane: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.layout_locked {
+            return false;
+        }
+        let expanded = self.center.expand_pane(pane);
+        if expanded {
+            self.notify_layout_changed(cx);
+            self.pane_sizes_changed(window, cx);
+        }
+        expanded
+    }
+
+    /// Undo the last [`Workspace::swap_pane_in_direction`] by swapping the active pane back.
+    pub fn swap_pane_back(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(direction) = self.last_swap_direction {
+            self.swap_pane_in_direction(direction.opposite(), window, cx);
+        }
+    }
+
+    fn handle_pane_focused(
+        &mut self,
+        pane: Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active_pane != pane {
+            self.active_pane = pane.clone();
+            self.last_active_center_pane = Some(pane.downgrade());
+            self.last_swap_direction = None;
+        }
+        self.panes_by_recent_use.retain(|p| p != &pane.downgrade());
+        self.panes_by_recent_use.insert(0, pane.downgrade());
+
+        if pane.read(cx).has_deferred_items() {
+            pane.update(cx, |pane, cx| pane.load_deferred_items(window, cx));
+        }
+
+        self.dismiss_zoomed_items_to_reveal(None, window, cx);
+        if pane.read(cx).is_zoomed() {
+            self.zoomed = Some(pane.downgrade().into());
+        } else {
+            self.zoomed = None;
+        }
+        self.zoomed_position = None;
+        cx.emit(Event::ZoomChanged);
+
+        cx.notify();
+    }
+
+    fn handle_pane_event(
+        &mut self,
+        pane: &Entity<Pane>,
+        event: &pane::Event,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            pane::Event::AddItem { item } => {
+                item.added_to_pane(self, pane, window, cx);
+                self.reported_empty = false;
+                cx.emit(Event::ItemAdded);
+            }
+            pane::Event::Split(direction) => {
+                self.split_and_clone(pane.clone(), *direction, None, window, cx);
+            }
+            pane::Event::Remove if self.bulk_close_depth > 0 => {
+                if !self
+                    .pending_pane_removals
+                    .iter()
+                    .any(|pending| pending.entity_id() == pane.entity_id())
+                {
+                    self.pending_pane_removals.push(pane.downgrade());
+                }
+            }
+            pane::Event::Remove => {
+                self.remove_pane(pane, window, cx);
+                self.report_if_empty(window, cx);
+            }
+            pane::Event::ActivateItem { local: _ } => {
+                cx.emit(Event::ActiveItemChanged);
+            }
+            pane::Event::ChangeLabel => {
+                cx.emit(Event::PaneLabelChanged(pane.clone()));
+            }
+            pane::Event::ChangeItemTitle => {
+                cx.emit(Event::ActiveItemChanged);
             }
             pane::Event::RemoveItem { item_id } => {
                 cx.emit(Event::ActiveItemChanged);
 
-                if let hash_map::Entry::Occupied(entry) = self.panes_by_item.entry(*item_id) {
-                    if entry.get().entity_id() == pane.entity_id() {
-                        entry.remove();
-                    }
+                if let hash_map::Entry::Occupied(entry) = self.panes_by_item.entry(*item_id) {
+                    if entry.get().entity_id() == pane.entity_id() {
+                        entry.remove();
+                    }
+                }
+                self.report_if_empty(window, cx);
+            }
+            pane::Event::Focus => {
+                self.handle_pane_focused(pane.clone(), window, cx);
+            }
+            pane::Event::ZoomIn => {
+                if *pane == self.active_pane {
+                    pane.update(cx, |pane, cx| pane.set_zoomed(true, window, cx));
+                    if pane.read(cx).has_focus(window, cx) {
+                        self.zoomed = Some(pane.downgrade().into());
+                        self.zoomed_position = None;
+                        cx.emit(Event::ZoomChanged);
+                    }
+                    cx.notify();
+                }
+            }
+            pane::Event::ZoomOut => {
+                pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
+                if self.zoomed_position.is_none() {
+                    self.zoomed = None;
+                    cx.emit(Event::ZoomChanged);
+                }
+                cx.notify();
+            }
+        }
+
+        self.serialize_workspace(window, cx);
+    }
+
+    /// Emit [`Event::WorkspaceEmpty`] once no pane has items left, closing the window too
+    /// if [`Workspace::set_close_window_when_empty`] is set.
+    fn report_if_empty(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.reported_empty || self.panes.iter().any(|pane| pane.read(cx).items_len() > 0) {
+            return;
+        }
+
+        self.reported_empty = true;
+        cx.emit(Event::WorkspaceEmpty);
+        if self.close_window_when_empty {
+            window.remove_window();
+        }
+    }
+
+    /// Zoom the active pane in, or out if it's already zoomed.
+    pub fn toggle_zoom(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.active_pane
+            .update(cx, |pane, cx| pane.toggle_zoom(window, cx));
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoomed.is_some()
+    }
+
+    /// The dock whose panel is zoomed, `None` if nothing or a center pane is zoomed.
+    pub fn zoomed_position(&self) -> Option<DockPosition> {
+        self.zoomed_position
+    }
+
+    /// Zoom out whichever pane or dock panel is zoomed.
+    pub fn zoom_out(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
+        }
+        for (_, dock) in self.docks() {
+            dock.update(cx, |dock, cx| dock.zoom_out(window, cx));
+        }
+
+        if self.zoomed.take().is_some() {
+            self.zoomed_position = None;
+            cx.emit(Event::ZoomChanged);
+        }
+        cx.notify();
+        self.serialize_workspace(window, cx);
+    }
+
+    /// Open the dock at `dock_side`, or close it if it's visible.
+    ///
+    /// Returns whether the dock is open afterwards.
+    pub fn toggle_dock(
+        &mut self,
+        dock_side: DockPosition,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let dock = self.dock_at(dock_side);
+        let mut focus_center = false;
+        let mut reveal_dock = false;
+        let mut was_open = false;
+        let is_open = dock.update(cx, |dock, cx| {
+            let other_is_zoomed = self.zoomed.is_some() && self.zoomed_position != Some(dock_side);
+            was_open = dock.is_open();
+            let was_visible = was_open && !other_is_zoomed;
+            dock.set_open(!was_visible, window, cx);
+
+            if let Some(active_panel) = dock.active_panel() {
+                if was_visible {
+                    if active_panel
+                        .panel_focus_handle(cx)
+                        .contains_focused(window, cx)
+                    {
+                        focus_center = true;
+                    }
+                } else {
+                    let focus_handle = &active_panel.panel_focus_handle(cx);
+                    window.focus(focus_handle);
+                    reveal_dock = true;
+                }
+            }
+
+            dock.is_open()
+        });
+
+        if is_open != was_open {
+            cx.emit(Event::DockStateChanged {
+                position: dock_side,
+                open: is_open,
+            });
+        }
+
+        if reveal_dock {
+            self.dismiss_zoomed_items_to_reveal(Some(dock_side), window, cx);
+        }
+
+        if focus_center {
+            self.active_pane.update(cx, |pane, _| pane.focus(window))
+        }
+
+        cx.notify();
+        self.serialize_workspace(window, cx);
+        is_open
+    }
+
+    /// Exchange the left and right docks, moving their panels with their sizes and open
+    /// states to the other side.
+    ///
+    /// Returns `false` without swapping if a panel of either dock can't be positioned on
+    /// the other side.
+    pub fn swap_side_docks(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let can_swap = [DockPosition::Left, DockPosition::Right]
+            .into_iter()
+            .all(|position| {
+                self.dock_at(position).read(cx).panels_can_position(
+                    position.opposite().unwrap(),
+                    window,
+                    cx,
+                )
+            });
+        if !can_swap {
+            return false;
+        }
+
+        mem::swap(&mut self.left_dock, &mut self.right_dock);
+        for position in [DockPosition::Left, DockPosition::Right] {
+            let dock = self.dock_at(position).clone();
+            dock.update(cx, |dock, cx| dock.set_position(position, window, cx));
+            let open = dock.read(cx).is_open();
+            if open
+                != self
+                    .dock_at(position.opposite().unwrap())
+                    .read(cx)
+                    .is_open()
+            {
+                cx.emit(Event::DockStateChanged { position, open });
+            }
+        }
+        if let Some(position) = self.zoomed_position {
+            self.zoomed_position = position.opposite().or(Some(position));
+        }
+
+        cx.notify();
+        self.serialize_workspace(window, cx);
+        true
+    }
+
+    /// Close every dock, returning how many of them were open.
+    pub fn close_all_docks(&mut self, window: &mut Window, cx: &mut Context<Self>) -> usize {
+        let mut closed = Vec::new();
+        for (_, dock) in self.docks() {
+            dock.update(cx, |dock, cx| {
+                if dock.is_open() {
+                    closed.push(dock.position());
+                }
+                dock.set_open(false, window, cx);
+            });
+        }
+
+        let closed_count = closed.len();
+        for position in closed {
+            cx.emit(Event::DockStateChanged {
+                position,
+                open: false,
+            });
+        }
+
+        // Return focus to the center pane that was active before a dock took it.
+        match self
+            .last_active_center_pane
+            .as_ref()
+            .and_then(|pane| pane.upgrade())
+        {
+            Some(pane) => pane.update(cx, |pane, _| pane.focus(window)),
+            None => cx.focus_self(window),
+        }
+        cx.notify();
+        self.serialize_workspace(window, cx);
+        closed_count
+    }
+
+    /// Close all docks like [`Workspace::close_all_docks`], remembering which were open with
+    /// their active panels and sizes so [`Workspace::restore_docks`] can bring them back,
+    /// e.g. for a distraction-free mode.
+    ///
+    /// Returns `false`, keeping what was remembered before, if no dock is open.
+    pub fn hide_all_docks(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let hidden_docks = self
+            .docks()
+            .into_iter()
+            .filter(|(_, dock)| dock.read(cx).is_open())
+            .map(|(position, dock)| {
+                let dock = dock.read(cx);
+                HiddenDock {
+                    position,
+                    active_panel_index: dock.active_panel_index(),
+                    size: dock.active_panel().map(|panel| panel.size(window, cx)),
+                }
+            })
+            .collect::<Vec<_>>();
+        if hidden_docks.is_empty() {
+            return false;
+        }
+
+        self.close_all_docks(window, cx);
+        self.hidden_docks = hidden_docks;
+        true
+    }
+
+    /// Reopen the docks hidden by [`Workspace::hide_all_docks`] as they were. Focus stays
+    /// where it is.
+    ///
+    /// Returns `false` if there are no hidden docks to restore.
+    pub fn restore_docks(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let hidden_docks = mem::take(&mut self.hidden_docks);
+        if hidden_docks.is_empty() {
+            return false;
+        }
+
+        for hidden_dock in hidden_docks {
+            let dock = self.dock_at(hidden_dock.position).clone();
+            let opened = dock.update(cx, |dock, cx| {
+                // Panels can be removed while the dock is hidden
+                let panels_len = dock.panels_len();
+                if panels_len == 0 {
+                    return false;
+                }
+                let was_open = dock.is_open();
+                dock.activate_panel(
+                    hidden_dock.active_panel_index.min(panels_len - 1),
+                    window,
+                    cx,
+                );
+                dock.set_open(true, window, cx);
+                if let Some(size) = hidden_dock.size {
+                    dock.resize_active_panel(Some(size), window, cx);
+                }
+                !was_open
+            });
+            if opened {
+                cx.emit(Event::DockStateChanged {
+                    position: hidden_dock.position,
+                    open: true,
+                });
+            }
+        }
+
+        cx.notify();
+        self.serialize_workspace(window, cx);
+        true
+    }
+
+    fn dismiss_zoomed_items_to_reveal(
+        &mut self,
+        dock_to_reveal: Option<DockPosition>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // If a center pane is zoomed, unzoom it.
+        for pane in &self.panes {
+            if pane != &self.active_pane || dock_to_reveal.is_some() {
+                pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
+            }
+        }
+
+        // If another dock is zoomed, hide it.
+        let mut focus_center = false;
+        for (_, dock) in self.docks() {
+            dock.update(cx, |dock, cx| {
+                if Some(dock.position()) != dock_to_reveal {
+                    if let Some(panel) = dock.active_panel() {
+                        if panel.is_zoomed(window, cx) {
+                            focus_center |=
+                                panel.panel_focus_handle(cx).contains_focused(window, cx);
+                            dock.set_open(false, window, cx);
+                        }
+                    }
+                }
+            });
+        }
+
+        if focus_center {
+            self.active_pane.update(cx, |pane, _| pane.focus(window))
+        }
+
+        if self.zoomed_position != dock_to_reveal {
+            self.zoomed = None;
+            self.zoomed_position = None;
+            cx.emit(Event::ZoomChanged);
+        }
+
+        cx.notify();
+    }
+
+    /// Returns a snapshot of the current layout: the center pane tree, the active pane
+    /// and the state of each dock.
+    pub fn serialized_state(&self, cx: &App) -> WorkspaceSnapshot {
+        let serialize_dock = |dock: &Entity<Dock>| {
+            let dock = dock.read(cx);
+            DockData {
+                visible: dock.is_open(),
+                active_panel: dock
+                    .active_panel()
+                    .map(|panel| panel.persistent_name().to_string()),
+                zoom: self.zoomed_position == Some(dock.position()),
+            }
+        };
+
+        WorkspaceSnapshot {
+            center_group: self.center.serialize(cx),
+            active_pane: self.active_pane.read(cx).serialized_id(),
+            docks: DockStructure {
+                left: serialize_dock(&self.left_dock),
+                bottom: serialize_dock(&self.bottom_dock),
+                right: serialize_dock(&self.right_dock),
+            },
+        }
+    }
+
+    /// Set where serialized layouts are sent, the workspace is serialized (debounced)
+    /// whenever its layout changes. The layouts are tagged with their format version, so
+    /// they can be given back to [`Workspace::restore_serialized`] by later versions.
+    pub fn set_serialization_sink(
+        &mut self,
+        sink: impl Fn(SerializedWorkspace, &mut App) + 'static,
+    ) {
+        self.serialization_sink = Some(Box::new(sink));
+    }
+
+    /// Rearrange the workspace into a layout received by the serialization sink, migrating
+    /// it from older format versions first. Open panes and items are reused by id, like
+    /// with [`Workspace::apply_layout`].
+    ///
+    /// Fails for layouts saved by a newer version of this crate.
+    pub fn restore_serialized(
+        &mut self,
+        serialized: SerializedWorkspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let serialized = serialized.migrate()?;
+        self.push_pane_layout_history();
+        self.batch_layout_update(window, cx, |this, window, cx| {
+            this.apply_snapshot(&serialized.snapshot, window, cx)
+        });
+        Ok(())
+    }
+
+    /// Save the current layout under `name`, replacing any layout previously saved with that name.
+    pub fn save_layout(&mut self, name: &str, cx: &App) {
+        let snapshot = self.serialized_state(cx);
+        self.layouts.insert(name.to_string(), snapshot);
+    }
+
+    pub fn layout_names(&self) -> impl Iterator<Item = &str> {
+        self.layouts.keys().map(String::as_str)
+    }
+
+    /// Rearrange the workspace into the layout saved under `name`.
+    ///
+    /// Open panes and items are reused by id. Items missing from the saved layout are
+    /// kept open in its active pane, and panes whose items are all gone are dropped.
+    /// Zoom is not restored.
+    ///
+    /// Returns `false` if there is no layout with this name.
+    pub fn apply_layout(
+        &mut self,
+        name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(snapshot) = self.layouts.get(name).cloned() else {
+            return false;
+        };
+
+        self.push_pane_layout_history();
+        self.batch_layout_update(window, cx, |this, window, cx| {
+            this.apply_snapshot(&snapshot, window, cx)
+        });
+        true
+    }
+
+    /// Rearrange the center panes into a grid of `rows` by `cols` panes, with the open items
+    /// distributed one per pane in tab order. Items beyond the last cell are added as tabs
+    /// to the last pane, and a grid with more cells than items is left with fewer panes.
+    ///
+    /// Returns `false` if the grid has no cells or the layout is locked.
+    pub fn arrange_grid(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.layout_locked || rows == 0 || cols == 0 {
+            return false;
+        }
+
+        let active_item_id = self
+            .active_pane
+            .read(cx)
+            .active_item()
+            .map(|item| item.item_id().as_u64());
+        let item_ids = self
+            .center
+            .panes()
+            .into_iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .map(|item| item.item_id().as_u64())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let cell_count = rows * cols;
+        let mut cells = vec![Vec::new(); cell_count];
+        for (ix, item_id) in item_ids.into_iter().enumerate() {
+            cells[ix.min(cell_count - 1)].push(item_id);
+        }
+
+        // Reuse the existing panes, keeping the active item in the active pane. Cells beyond
+        // them get an id no pane has, so new panes are created for them.
+        let active_pane_id = self.active_pane.read(cx).serialized_id();
+        let mut other_pane_ids = self
+            .panes
+            .iter()
+            .map(|pane| pane.read(cx).serialized_id())
+            .filter(|pane_id| *pane_id != active_pane_id);
+        let mut cells = cells
+            .into_iter()
+            .map(|items| {
+                let active_item_index = items
+                    .iter()
+                    .position(|item_id| Some(*item_id) == active_item_id);
+                let pane_id = if active_item_index.is_some() {
+                    active_pane_id
+                } else {
+                    other_pane_ids.next().unwrap_or_default()
+                };
+                SerializedPaneGroup::Pane(SerializedPane {
+                    pane_id,
+                    items,
+                    active_item_index: active_item_index.unwrap_or(0),
+                    zoomed: false,
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        let row_groups = (0..rows)
+            .map(|_| SerializedPaneGroup::Group {
+                axis: Axis::Horizontal,
+                flexes: vec![1.; cols],
+                children: cells.by_ref().take(cols).collect(),
+            })
+            .collect();
+
+        let snapshot = WorkspaceSnapshot {
+            center_group: SerializedPaneGroup::Group {
+                axis: Axis::Vertical,
+                flexes: vec![1.; rows],
+                children: row_groups,
+            },
+            active_pane: active_pane_id,
+            ..self.serialized_state(cx)
+        };
+        self.push_pane_layout_history();
+        self.batch_layout_update(window, cx, |this, window, cx| {
+            this.apply_snapshot(&snapshot, window, cx)
+        });
+        true
+    }
+
+    /// Run `f`, which may split, remove and rearrange panes any number of times, then notify
+    /// and serialize the workspace once at the end instead of after every change.
+    ///
+    /// Batches can be nested, only the outermost one notifies and serializes.
+    pub fn batch_layout_update<R>(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) -> R,
+    ) -> R {
+        self.layout_batch_depth += 1;
+        let result = f(self, window, cx);
+        self.layout_batch_depth -= 1;
+
+        if self.layout_batch_depth == 0 {
+            if mem::take(&mut self.layout_batch_notify) {
+                cx.notify();
+            }
+            if mem::take(&mut self.layout_batch_serialize) {
+                self.serialize_workspace(window, cx);
+            }
+        }
+        result
+    }
+
+    /// Notify after a structural change of the center group, deferred to the end of a
+    /// [`Workspace::batch_layout_update`]. Debug builds check the changed group with
+    /// [`PaneGroup::validate`] first.
+    fn notify_layout_changed(&mut self, cx: &mut Context<Self>) {
+        #[cfg(debug_assertions)]
+        if let Err(error) = self.center.validate() {
+            panic!("{error}");
+        }
+        if self.layout_batch_depth > 0 {
+            self.layout_batch_notify = true;
+        } else {
+            cx.notify();
+        }
+    }
+
+    fn push_pane_layout_history(&mut self) {
+        self.record_pane_layout(self.center.deep_clone(), self.active_pane.clone());
+    }
+
+    fn record_pane_layout(&mut self, center: PaneGroup, active_pane: Entity<Pane>) {
+        if self.pane_layout_history.len() == MAX_PANE_LAYOUT_HISTORY {
+            self.pane_layout_history.pop_front();
+        }
+        self.pane_layout_history.push_back((center, active_pane));
+    }
+
+    /// Restore the pane layout from before the last split, swap, removal or applied layout.
+    ///
+    /// Items in panes that are not part of the restored layout are moved into its active
+    /// pane, and panes that were removed since come back empty.
+    ///
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo_pane_layout(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if self.layout_locked {
+            return false;
+        }
+        let Some((center, active_pane)) = self.pane_layout_history.pop_back() else {
+            return false;
+        };
+
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
+        }
+        if self.zoomed.take().is_some() {
+            self.zoomed_position = None;
+            cx.emit(Event::ZoomChanged);
+        }
+
+        let restored_panes = center.panes().into_iter().cloned().collect::<Vec<_>>();
+        let active_pane = if restored_panes.contains(&active_pane) {
+            active_pane
+        } else {
+            restored_panes[0].clone()
+        };
+
+        let previous_panes = std::mem::replace(&mut self.panes, restored_panes.clone());
+        for pane in &previous_panes {
+            if restored_panes.contains(pane) {
+                continue;
+            }
+
+            let items = pane.read(cx).items().cloned().collect::<Vec<_>>();
+            pane.update(cx, |pane, cx| pane.set_items(Vec::new(), 0, window, cx));
+            active_pane.update(cx, |active_pane, cx| {
+                for item in items {
+                    active_pane.add_item(item, false, false, None, window, cx);
                 }
+            });
+            if self.last_active_center_pane == Some(pane.downgrade()) {
+                self.last_active_center_pane = None;
             }
-            pane::Event::Focus => {
-                self.handle_pane_focused(pane.clone(), window, cx);
+            cx.emit(Event::PaneRemoved);
+        }
+        for pane in &restored_panes {
+            if !previous_panes.contains(pane) {
+                cx.emit(Event::PaneAdded(pane.clone()));
             }
-            pane::Event::ZoomIn => {
-                if *pane == self.active_pane {
-                    pane.update(cx, |pane, cx| pane.set_zoomed(true, window, cx));
-                    if pane.read(cx).has_focus(window, cx) {
-                        self.zoomed = Some(pane.downgrade().into());
-                        self.zoomed_position = None;
-                        cx.emit(Event::ZoomChanged);
-                    }
-                    cx.notify();
+        }
+
+        self.center = center;
+        self.active_pane = active_pane.clone();
+        self.last_active_center_pane = Some(active_pane.downgrade());
+        cx.focus_view(&active_pane, window);
+
+        self.notify_layout_changed(cx);
+        self.serialize_workspace(window, cx);
+        true
+    }
+
+    fn apply_snapshot(
+        &mut self,
+        snapshot: &WorkspaceSnapshot,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
+        }
+        if self.zoomed.take().is_some() {
+            self.zoomed_position = None;
+            cx.emit(Event::ZoomChanged);
+        }
+
+        let previous_panes = self.panes.clone();
+        let mut open_items = previous_panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items().cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut unused_panes = previous_panes.clone();
+
+        let root = self
+            .build_layout_member(
+                &snapshot.center_group,
+                &mut open_items,
+                &mut unused_panes,
+                window,
+                cx,
+            )
+            .unwrap_or_else(|| {
+                let pane = if unused_panes.contains(&self.active_pane) {
+                    unused_panes.retain(|pane| pane != &self.active_pane);
+                    self.active_pane.clone()
+                } else {
+                    self.add_pane(window, cx)
+                };
+                pane.update(cx, |pane, cx| pane.set_items(Vec::new(), 0, window, cx));
+                Member::Pane(pane)
+            });
+        self.center = PaneGroup { root };
+        self.panes = self.center.panes().into_iter().cloned().collect();
+
+        let active_pane = self
+            .panes
+            .iter()
+            .find(|pane| pane.read(cx).serialized_id() == snapshot.active_pane)
+            .unwrap_or(&self.panes[0])
+            .clone();
+        if !open_items.is_empty() {
+            active_pane.update(cx, |pane, cx| {
+                for item in open_items {
+                    pane.add_item(item, false, false, None, window, cx);
                 }
+            });
+        }
+
+        for pane in unused_panes {
+            pane.update(cx, |pane, cx| pane.set_items(Vec::new(), 0, window, cx));
+            if self.last_active_center_pane == Some(pane.downgrade()) {
+                self.last_active_center_pane = None;
             }
-            pane::Event::ZoomOut => {
-                pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
-                if self.zoomed_position.is_none() {
-                    self.zoomed = None;
-                    cx.emit(Event::ZoomChanged);
+            cx.emit(Event::PaneRemoved);
+        }
+
+        self.active_pane = active_pane.clone();
+        self.last_active_center_pane = Some(active_pane.downgrade());
+        cx.focus_view(&active_pane, window);
+
+        for (position, dock) in self.docks() {
+            let data = snapshot.docks.dock(position);
+            let changed = dock.update(cx, |dock, cx| {
+                if let Some(ix) = data
+                    .active_panel
+                    .as_ref()
+                    .and_then(|name| dock.panel_index_for_persistent_name(name))
+                {
+                    dock.activate_panel(ix, window, cx);
                 }
-                cx.notify();
+                let was_open = dock.is_open();
+                dock.set_open(data.visible, window, cx);
+                was_open != dock.is_open()
+            });
+            if changed {
+                cx.emit(Event::DockStateChanged {
+                    position,
+                    open: data.visible,
+                });
             }
         }
 
+        self.notify_layout_changed(cx);
         self.serialize_workspace(window, cx);
     }
 
-    pub fn toggle_dock(
+    /// Build the member for a serialized group, reusing the open panes and items.
+    ///
+    /// Returns `None` when none of the group's items are still open.
+    fn build_layout_member(
         &mut self,
-        dock_side: DockPosition,
+        group: &SerializedPaneGroup,
+        open_items: &mut Vec<Box<dyn ItemHandle>>,
+        unused_panes: &mut Vec<Entity<Pane>>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
-        let dock = match dock_side {
-            DockPosition::Left => &self.left_dock,
-            DockPosition::Bottom => &self.bottom_dock,
-            DockPosition::Right => &self.right_dock,
-        };
-        let mut focus_center = false;
-        let mut reveal_dock = false;
-        dock.update(cx, |dock, cx| {
-            let other_is_zoomed = self.zoomed.is_some() && self.zoomed_position != Some(dock_side);
-            let was_visible = dock.is_open() && !other_is_zoomed;
-            dock.set_open(!was_visible, window, cx);
-
-            if let Some(active_panel) = dock.active_panel() {
-                if was_visible {
-                    if active_panel
-                        .panel_focus_handle(cx)
-                        .contains_focused(window, cx)
+    ) -> Option<Member> {
+        match group {
+            SerializedPaneGroup::Group {
+                axis,
+                flexes,
+                children,
+            } => {
+                let mut members = Vec::new();
+                let mut member_flexes = Vec::new();
+                for (ix, child) in children.iter().enumerate() {
+                    if let Some(member) =
+                        self.build_layout_member(child, open_items, unused_panes, window, cx)
                     {
-                        focus_center = true;
+                        members.push(member);
+                        member_flexes.push(flexes.get(ix).copied().unwrap_or(1.));
                     }
-                } else {
-                    let focus_handle = &active_panel.panel_focus_handle(cx);
-                    window.focus(focus_handle);
-                    reveal_dock = true;
+                }
+
+                match members.len() {
+                    0 => None,
+                    1 => members.pop(),
+                    _ => Some(Member::Axis(PaneAxis::load(
+                        *axis,
+                        members,
+                        Some(member_flexes),
+                    ))),
                 }
             }
-        });
+            SerializedPaneGroup::Pane(serialized_pane) => {
+                let active_item_id = serialized_pane
+                    .items
+                    .get(serialized_pane.active_item_index)
+                    .copied();
+                let items = serialized_pane
+                    .items
+                    .iter()
+                    .filter_map(|item_id| {
+                        let ix = open_items
+                            .iter()
+                            .position(|item| item.item_id().as_u64() == *item_id)?;
+                        Some(open_items.remove(ix))
+                    })
+                    .collect::<Vec<_>>();
+                if items.is_empty() {
+                    return None;
+                }
 
-        if reveal_dock {
-            self.dismiss_zoomed_items_to_reveal(Some(dock_side), window, cx);
+                let active_item_index = items
+                    .iter()
+                    .position(|item| Some(item.item_id().as_u64()) == active_item_id)
+                    .unwrap_or(0);
+                let pane = match unused_panes
+                    .iter()
+                    .position(|pane| pane.read(cx).serialized_id() == serialized_pane.pane_id)
+                {
+                    Some(ix) => unused_panes.remove(ix),
+                    None => {
+                        let pane = self.add_pane(window, cx);
+                        // Keep the id from the layout, so it's stable across restores
+                        let id = serialized_pane.pane_id;
+                        let id_in_use = self
+                            .panes
+                            .iter()
+                            .chain(unused_panes.iter())
+                            .any(|pane| pane.read(cx).serialized_id() == id);
+                        if id != SerializedPaneId::default() && !id_in_use {
+                            pane.update(cx, |pane, _| pane.set_serialized_id(id));
+                            self.next_serialized_pane_id =
+                                self.next_serialized_pane_id.max(id.0 + 1);
+                        }
+                        pane
+                    }
+                };
+                pane.update(cx, |pane, cx| {
+                    pane.set_items(items, active_item_index, window, cx)
+                });
+                Some(Member::Pane(pane))
+            }
         }
+    }
 
-        if focus_center {
-            self.active_pane.update(cx, |pane, _| pane.focus(window))
+    pub(crate) fn serialize_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.layout_batch_depth > 0 || self.bulk_close_depth > 0 {
+            self.layout_batch_serialize = true;
+            return;
+        }
+        if self.serialization_sink.is_none() || self._schedule_serialize.is_some() {
+            return;
         }
 
-        cx.notify();
-        self.serialize_workspace(window, cx);
+        self._schedule_serialize = Some(cx.spawn_in(window, |this, mut cx| async move {
+            cx.background_executor()
+                .timer(Duration::from_millis(100))
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.serialize_workspace_internal(cx);
+                this._schedule_serialize.take();
+            })
+            .log_err();
+        }));
     }
 
-    pub fn close_all_docks(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let docks = [&self.left_dock, &self.bottom_dock, &self.right_dock];
+    /// Called after the flexes of the center group changed, by resizing or resetting them.
+    pub(crate) fn pane_sizes_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.serialize_workspace(window, cx);
+        if self._schedule_pane_sizes_changed.is_some() {
+            return;
+        }
+
+        self._schedule_pane_sizes_changed = Some(cx.spawn_in(window, |this, mut cx| async move {
+            cx.background_executor()
+                .timer(Duration::from_millis(100))
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this._schedule_pane_sizes_changed.take();
+                cx.emit(Event::PaneSizesChanged);
+            })
+            .log_err();
+        }));
+    }
 
-        for dock in docks {
-            dock.update(cx, |dock, cx| {
-                dock.set_open(false, window, cx);
-            });
+    fn serialize_workspace_internal(&self, cx: &mut App) {
+        let serialized = SerializedWorkspace::new(self.serialized_state(cx));
+        if let Some(sink) = self.serialization_sink.as_ref() {
+            sink(serialized, cx);
         }
+    }
+}
 
-        cx.focus_self(window);
-        cx.notify();
-        self.serialize_workspace(window, cx);
+#[cfg(any(test, feature = "test-support"))]
+use crate::item::test::TestItem;
+
+#[cfg(any(test, feature = "test-support"))]
+impl Workspace {
+    /// Open a workspace in a test window.
+    ///
+    /// Panes can then be filled with [`Workspace::add_test_item`] and
+    /// [`Workspace::add_test_pane`], and the resulting tree inspected with
+    /// [`Workspace::center`].
+    pub fn test_new(
+        cx: &mut gpui::TestAppContext,
+    ) -> (Entity<Workspace>, &mut gpui::VisualTestContext) {
+        cx.update(ui::init);
+        cx.add_window_view(|window, cx| Workspace::new(None, window, cx))
     }
 
-    fn dismiss_zoomed_items_to_reveal(
+    pub fn center(&self) -> &PaneGroup {
+        &self.center
+    }
+
+    /// Open a new [`TestItem`] labelled `label` in `pane` and activate it.
+    pub fn add_test_item(
         &mut self,
-        dock_to_reveal: Option<DockPosition>,
+        pane: &Entity<Pane>,
+        label: &str,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
-        // If a center pane is zoomed, unzoom it.
-        for pane in &self.panes {
-            if pane != &self.active_pane || dock_to_reveal.is_some() {
-                pane.update(cx, |pane, cx| pane.set_zoomed(false, window, cx));
+    ) -> Entity<TestItem> {
+        let label = label.to_string();
+        let item = cx.new(|cx| TestItem::new(cx).with_label(label));
+        pane.update(cx, |pane, cx| {
+            pane.add_item(Box::new(item.clone()), true, true, None, window, cx)
+        });
+        item
+    }
+
+    /// Split `pane` in `direction` into a new pane holding a [`TestItem`] labelled `label`.
+    ///
+    /// Panics if the split fails, e.g. since the layout is locked.
+    pub fn add_test_pane(
+        &mut self,
+        pane: &Entity<Pane>,
+        direction: SplitDirection,
+        label: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Pane> {
+        let new_pane = self
+            .split_pane(pane.clone(), direction, None, window, cx)
+            .expect("failed to split test pane");
+        self.add_test_item(&new_pane, label, window, cx);
+        new_pane
+    }
+
+    /// Seed the bounds normally set when the workspace is painted, to test navigation and
+    /// resizing without rendering. The center group is laid out to fill `bounds` as if
+    /// no docks were open, see [`PaneGroup::set_bounds`].
+    pub fn set_bounds(&mut self, bounds: Bounds<Pixels>) {
+        self.bounds = bounds;
+        self.center.set_bounds(bounds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::dock::test::TestPanel;
+
+    const DOCKS: [DockPosition; 3] = [
+        DockPosition::Left,
+        DockPosition::Bottom,
+        DockPosition::Right,
+    ];
+
+    fn all_docks_open() -> impl Iterator<Item = DocksOpen> {
+        (0..8).map(|bits| DocksOpen {
+            left: bits & 1 != 0,
+            right: bits & 2 != 0,
+            bottom: bits & 4 != 0,
+        })
+    }
+
+    fn all_origins() -> impl Iterator<Item = NavOrigin> {
+        [true, false]
+            .into_iter()
+            .map(|has_pane_in_direction| NavOrigin::Center {
+                has_pane_in_direction,
+            })
+            .chain(DOCKS.into_iter().map(NavOrigin::Dock))
+    }
+
+    /// The targets moving in `direction` from `origin` tries, most preferred first.
+    fn preferred_targets(origin: NavOrigin, direction: SplitDirection) -> Vec<NavTarget> {
+        use DockPosition::*;
+        use NavTarget::*;
+
+        match (origin, direction) {
+            (
+                NavOrigin::Center {
+                    has_pane_in_direction: true,
+                },
+                _,
+            ) => vec![PaneInDirection],
+            (NavOrigin::Center { .. }, SplitDirection::Up) => vec![],
+            (NavOrigin::Center { .. }, SplitDirection::Down) => vec![Dock(Bottom)],
+            (NavOrigin::Center { .. }, SplitDirection::Left) => vec![Dock(Left)],
+            (NavOrigin::Center { .. }, SplitDirection::Right) => vec![Dock(Right)],
+
+            (NavOrigin::Dock(Left), SplitDirection::Right) => {
+                vec![LastActivePane, Dock(Bottom), Dock(Right)]
+            }
+            (NavOrigin::Dock(Right), SplitDirection::Left) => {
+                vec![LastActivePane, Dock(Bottom), Dock(Left)]
+            }
+            (NavOrigin::Dock(_), SplitDirection::Up) => vec![LastActivePane],
+            (NavOrigin::Dock(Left | Right), SplitDirection::Down) => {
+                vec![Dock(Bottom), LastActivePane]
             }
+            (NavOrigin::Dock(Bottom), SplitDirection::Left) => vec![Dock(Left)],
+            (NavOrigin::Dock(Bottom), SplitDirection::Right) => vec![Dock(Right)],
+            (NavOrigin::Dock(Left), SplitDirection::Left)
+            | (NavOrigin::Dock(Right), SplitDirection::Right)
+            | (NavOrigin::Dock(Bottom), SplitDirection::Down) => vec![],
         }
+    }
 
-        // If another dock is zoomed, hide it.
-        let mut focus_center = false;
-        for dock in [&self.left_dock, &self.right_dock, &self.bottom_dock] {
-            dock.update(cx, |dock, cx| {
-                if Some(dock.position()) != dock_to_reveal {
-                    if let Some(panel) = dock.active_panel() {
-                        if panel.is_zoomed(window, cx) {
-                            focus_center |=
-                                panel.panel_focus_handle(cx).contains_focused(window, cx);
-                            dock.set_open(false, window, cx);
-                        }
+    #[test]
+    fn test_resolve_navigation_target_all_cases() {
+        for origin in all_origins() {
+            for direction in SplitDirection::all() {
+                for docks_open in all_docks_open() {
+                    for has_last_active_pane in [true, false] {
+                        let expected = preferred_targets(origin, direction)
+                            .into_iter()
+                            .find(|target| match target {
+                                NavTarget::PaneInDirection => true,
+                                NavTarget::LastActivePane => has_last_active_pane,
+                                NavTarget::Dock(position) => docks_open.is_open(*position),
+                                NavTarget::None => false,
+                            })
+                            .unwrap_or(NavTarget::None);
+                        assert_eq!(
+                            resolve_navigation_target(
+                                origin,
+                                direction,
+                                docks_open,
+                                has_last_active_pane
+                            ),
+                            expected,
+                            "moving {direction:?} from {origin:?} with {docks_open:?}, \
+                             has_last_active_pane: {has_last_active_pane}"
+                        );
                     }
                 }
-            });
+            }
         }
+    }
 
-        if focus_center {
-            self.active_pane.update(cx, |pane, _| pane.focus(window))
+    #[test]
+    fn test_resolve_navigation_target_falls_back_to_center() {
+        let no_docks = DocksOpen::default();
+        for position in DOCKS {
+            assert_eq!(
+                resolve_navigation_target(
+                    NavOrigin::Dock(position),
+                    SplitDirection::Up,
+                    no_docks,
+                    true
+                ),
+                NavTarget::LastActivePane
+            );
         }
+        // The bottom dock is preferred when open, the center otherwise
+        for position in [DockPosition::Left, DockPosition::Right] {
+            assert_eq!(
+                resolve_navigation_target(
+                    NavOrigin::Dock(position),
+                    SplitDirection::Down,
+                    no_docks,
+                    true
+                ),
+                NavTarget::LastActivePane
+            );
+            assert_eq!(
+                resolve_navigation_target(
+                    NavOrigin::Dock(position),
+                    SplitDirection::Down,
+                    DocksOpen {
+                        bottom: true,
+                        ..no_docks
+                    },
+                    true
+                ),
+                NavTarget::Dock(DockPosition::Bottom)
+            );
+        }
+    }
 
-        if self.zoomed_position != dock_to_reveal {
-            self.zoomed = None;
-            self.zoomed_position = None;
-            cx.emit(Event::ZoomChanged);
+    #[test]
+    fn test_resolve_navigation_target_without_target() {
+        let all_docks = DocksOpen {
+            left: true,
+            right: true,
+            bottom: true,
+        };
+        let center = NavOrigin::Center {
+            has_pane_in_direction: false,
+        };
+        // Nothing is above the center, and nothing is past the window edges
+        assert_eq!(
+            resolve_navigation_target(center, SplitDirection::Up, all_docks, true),
+            NavTarget::None
+        );
+        for (position, direction) in [
+            (DockPosition::Left, SplitDirection::Left),
+            (DockPosition::Right, SplitDirection::Right),
+            (DockPosition::Bottom, SplitDirection::Down),
+        ] {
+            assert_eq!(
+                resolve_navigation_target(NavOrigin::Dock(position), direction, all_docks, true),
+                NavTarget::None
+            );
+        }
+        // Closed docks aren't targets
+        for direction in [
+            SplitDirection::Left,
+            SplitDirection::Right,
+            SplitDirection::Down,
+        ] {
+            assert_eq!(
+                resolve_navigation_target(center, direction, DocksOpen::default(), true),
+                NavTarget::None
+            );
         }
+        assert_eq!(
+            resolve_navigation_target(
+                NavOrigin::Dock(DockPosition::Left),
+                SplitDirection::Right,
+                DocksOpen::default(),
+                false
+            ),
+            NavTarget::None
+        );
+    }
 
-        cx.notify();
+    #[gpui::test]
+    async fn test_closing_zoomed_pane_clears_zoom(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        let (first, second, second_item) = workspace.update_in(cx, |workspace, window, cx| {
+            let first = workspace.active_pane().clone();
+            workspace.add_test_item(&first, "first", window, cx);
+            let second =
+                workspace.add_test_pane(&first, SplitDirection::Right, "second", window, cx);
+            let second_item = second.read(cx).items().next().unwrap().item_id();
+            (first, second, second_item)
+        });
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            assert_eq!(workspace.active_pane(), &second);
+            workspace.toggle_zoom(window, cx);
+        });
+        cx.run_until_parked();
+        workspace.read_with(cx, |workspace, cx| {
+            assert!(workspace.is_zoomed());
+            assert!(second.read(cx).is_zoomed());
+        });
+
+        second
+            .update_in(cx, |pane, window, cx| {
+                pane.close_item_by_id(second_item, window, cx)
+            })
+            .detach();
+        cx.run_until_parked();
+
+        workspace.read_with(cx, |workspace, cx| {
+            assert_eq!(workspace.panes(), &[first.clone()]);
+            assert!(workspace.zoomed.is_none());
+            assert!(!workspace.is_zoomed());
+            assert_eq!(workspace.active_pane(), &first);
+            assert!(!workspace.active_pane().read(cx).is_zoomed());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_close_all_docks_refocuses_last_active_center_pane(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        let first = workspace.update_in(cx, |workspace, window, cx| {
+            let first = workspace.active_pane().clone();
+            workspace.add_test_item(&first, "first", window, cx);
+            workspace.add_test_pane(&first, SplitDirection::Right, "second", window, cx);
+            first
+        });
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            first.update(cx, |pane, _| pane.focus(window));
+            for dock in [workspace.left_dock(), workspace.bottom_dock()] {
+                dock.update(cx, |dock, cx| dock.set_open(true, window, cx));
+            }
+        });
+        cx.run_until_parked();
+
+        let closed = workspace.update_in(cx, |workspace, window, cx| {
+            window.blur();
+            workspace.close_all_docks(window, cx)
+        });
+        cx.run_until_parked();
+
+        assert_eq!(closed, 2);
+        cx.update(|window, cx| {
+            let workspace = workspace.read(cx);
+            assert!(workspace
+                .docks()
+                .iter()
+                .all(|(_, dock)| !dock.read(cx).is_open()));
+            assert!(first.read(cx).focus_handle(cx).contains_focused(window, cx));
+            assert_eq!(workspace.active_pane(), &first);
+        });
+
+        // Nothing left open to close, focus stays where it is
+        let closed = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.close_all_docks(window, cx)
+        });
+        assert_eq!(closed, 0);
+    }
+
+    #[gpui::test]
+    async fn test_force_remove_last_pane_replaces_it(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        // Removing the only pane used to unwrap the last of an empty `panes`
+        workspace.update_in(cx, |workspace, window, cx| {
+            let pane = workspace.active_pane().clone();
+            workspace.force_remove_pane(&pane, window, cx);
+
+            assert_eq!(workspace.panes().len(), 1);
+            assert_ne!(workspace.active_pane(), &pane);
+            assert_eq!(
+                workspace.center().test_panes(),
+                vec![workspace.active_pane()]
+            );
+            assert_eq!(
+                workspace
+                    .last_active_center_pane
+                    .as_ref()
+                    .and_then(|pane| pane.upgrade())
+                    .as_ref(),
+                Some(workspace.active_pane())
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_remove_panes_while_out_of_sync_with_center(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        let (first, second, third) = workspace.update_in(cx, |workspace, window, cx| {
+            let first = workspace.active_pane().clone();
+            workspace.add_test_item(&first, "first", window, cx);
+            let second =
+                workspace.add_test_pane(&first, SplitDirection::Right, "second", window, cx);
+            let third = workspace.add_test_pane(&second, SplitDirection::Down, "third", window, cx);
+            (first, second, third)
+        });
+        cx.run_until_parked();
+        let item_ids = |pane: &Entity<Pane>, cx: &App| {
+            pane.read(cx)
+                .items()
+                .map(|item| item.item_id())
+                .collect::<Vec<_>>()
+        };
+        let (first_items, third_items) =
+            cx.update(|_, cx| (item_ids(&first, cx), item_ids(&third, cx)));
+
+        // Remove the middle pane while `panes` only lists that one
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.panes.retain(|pane| pane == &second);
+            workspace.remove_pane(&second, window, cx);
+
+            assert_eq!(workspace.center().test_panes(), vec![&first, &third]);
+            assert_eq!(workspace.panes(), &[first.clone(), third.clone()]);
+            assert_eq!(item_ids(&first, cx), first_items);
+            assert_eq!(item_ids(&third, cx), third_items);
+        });
+        cx.run_until_parked();
+
+        // Then the first one while `panes` lists none
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.panes.clear();
+            workspace.remove_pane(&first, window, cx);
+
+            assert_eq!(workspace.center().test_panes(), vec![&third]);
+            assert_eq!(workspace.panes(), &[third.clone()]);
+        });
+        cx.run_until_parked();
+
+        workspace.read_with(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane(), &third);
+            assert_eq!(item_ids(&third, cx), third_items);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_sync_panes_with_center(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        workspace.update_in(cx, |workspace, window, cx| {
+            let first = workspace.active_pane().clone();
+            workspace.add_test_item(&first, "first", window, cx);
+            let second =
+                workspace.add_test_pane(&first, SplitDirection::Right, "second", window, cx);
+            workspace.add_test_pane(&second, SplitDirection::Down, "third", window, cx);
+            workspace.active_pane = second.clone();
+
+            // Lose the active pane and list another one twice
+            workspace.panes.retain(|pane| pane != &second);
+            workspace.panes.push(first.clone());
+            assert_eq!(workspace.panes().len(), 3);
+
+            workspace.sync_panes_with_center();
+
+            let panes = workspace
+                .panes()
+                .iter()
+                .map(|pane| pane.entity_id())
+                .collect::<Vec<_>>();
+            let center_panes = workspace
+                .center()
+                .panes()
+                .into_iter()
+                .map(|pane| pane.entity_id())
+                .collect::<HashSet<_>>();
+            assert_eq!(panes.len(), center_panes.len());
+            assert_eq!(panes.iter().copied().collect::<HashSet<_>>(), center_panes);
+            assert!(workspace.panes().contains(workspace.active_pane()));
+            assert_eq!(workspace.active_pane(), &second);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_find_pane_in_direction_before_and_after_layout(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        let (left, right) = workspace.update_in(cx, |workspace, window, cx| {
+            let left = workspace.active_pane().clone();
+            workspace.add_test_item(&left, "left", window, cx);
+            let right = workspace.add_test_pane(&left, SplitDirection::Right, "right", window, cx);
+            left.update(cx, |pane, _| pane.focus(window));
+            (left, right)
+        });
+        cx.run_until_parked();
+
+        // The test window may already have been drawn, forget its layout to start from an
+        // unpainted workspace.
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.set_bounds(Bounds::default());
+            assert_eq!(workspace.active_pane(), &left);
+            assert!(!workspace.is_laid_out());
+            for direction in [
+                SplitDirection::Left,
+                SplitDirection::Right,
+                SplitDirection::Up,
+                SplitDirection::Down,
+            ] {
+                assert_eq!(
+                    workspace.find_pane_in_direction(direction, window, cx),
+                    None
+                );
+            }
+            for direction in [SplitDirection::Up, SplitDirection::Down] {
+                workspace.activate_pane_in_direction(direction, window, cx);
+            }
+        });
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            assert_eq!(workspace.active_pane(), &left);
+            assert!(left.read(cx).focus_handle(cx).contains_focused(window, cx));
+
+            workspace.set_bounds(Bounds::new(
+                gpui::point(px(0.), px(0.)),
+                size(px(800.), px(600.)),
+            ));
+            assert!(workspace.is_laid_out());
+            assert_eq!(
+                workspace.find_pane_in_direction(SplitDirection::Right, window, cx),
+                Some(right.clone())
+            );
+            assert_eq!(
+                workspace.find_pane_in_direction(SplitDirection::Left, window, cx),
+                None
+            );
+            assert_eq!(
+                workspace.find_pane_in_direction(SplitDirection::Up, window, cx),
+                None
+            );
+        });
     }
 
-    pub(crate) fn serialize_workspace(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
-        // if self._schedule_serialize.is_none() {
-        //     self._schedule_serialize = Some(cx.spawn(|this, mut cx| async move {
-        //         cx.background_executor()
-        //             .timer(Duration::from_millis(100))
-        //             .await;
-        //         this.update(&mut cx, |this, cx| {
-        //             this.serialize_workspace_internal(cx).detach();
-        //             this._schedule_serialize.take();
-        //         })
-        //         .log_err();
-        //     }));
-        // }
+    #[gpui::test]
+    async fn test_swap_side_docks_with_a_panel_that_cant_move(cx: &mut TestAppContext) {
+        let (workspace, cx) = Workspace::test_new(cx);
+        let movable = workspace.update_in(cx, |workspace, window, cx| {
+            let movable = cx.new(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(movable.clone(), window, cx);
+
+            assert!(workspace.swap_side_docks(window, cx));
+            assert_eq!(movable.read(cx).position, DockPosition::Left);
+            assert_eq!(workspace.left_dock().read(cx).panels_len(), 1);
+            movable
+        });
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let left_only = cx.new(|cx| TestPanel::new(DockPosition::Left, cx).fixed());
+            workspace.add_panel(left_only.clone(), window, cx);
+            let (left_dock, right_dock) = (
+                workspace.left_dock().clone(),
+                workspace.right_dock().clone(),
+            );
+
+            assert!(!workspace.swap_side_docks(window, cx));
+            assert_eq!(workspace.left_dock(), &left_dock);
+            assert_eq!(workspace.right_dock(), &right_dock);
+            assert_eq!(left_dock.read(cx).position(), DockPosition::Left);
+            assert_eq!(left_dock.read(cx).panels_len(), 2);
+            assert_eq!(left_only.read(cx).position, DockPosition::Left);
+            assert_eq!(movable.read(cx).position, DockPosition::Left);
+        });
     }
 }